@@ -0,0 +1,43 @@
+//! This module holds the processing applied to raw analog input before it is
+//! used by gameplay code. Input is treated as a 2D vector, so that deadzones
+//! are radial and do not favour the axes.
+
+use serde::{Deserialize, Serialize};
+
+use crate::util::prelude::*;
+use crate::vector::prelude::*;
+
+/// The configuration of the analog input response.
+// TODO: hopefully remove the `Default` derivation pending this issue:
+// https://github.com/amethyst/amethyst/issues/1954
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct AnalogConfig {
+    /// Input magnitudes below this value are treated as no input at all.
+    pub deadzone: f32,
+    /// Input magnitudes above this value are treated as full deflection.
+    pub outer_deadzone: f32,
+    /// The exponent of the response curve applied between the deadzones. A
+    /// value of `1.0` gives a linear response, while larger values give finer
+    /// control at small deflections.
+    pub response_exponent: f32,
+}
+
+impl AnalogConfig {
+    /// Applies the deadzones and response curve to `raw`. The direction of the
+    /// input is preserved, and the resulting magnitude lies between `0.0` and
+    /// `1.0`. Keyboard input, where diagonals have a raw magnitude above one,
+    /// always results in full deflection.
+    pub fn process(&self, raw: Vec2) -> Vec2 {
+        let magnitude = raw.norm();
+        if magnitude <= self.deadzone || magnitude == 0.0 {
+            return Vec2::zeros();
+        }
+
+        let range =
+            (self.outer_deadzone - self.deadzone).max(std::f32::EPSILON);
+        let scaled = clamp((magnitude - self.deadzone) / range, 0.0, 1.0)
+            .powf(self.response_exponent);
+
+        raw / magnitude * scaled
+    }
+}
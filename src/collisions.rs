@@ -14,6 +14,15 @@ pub mod box_collision {
 
     pub use super::line_intersects_line;
     pub use super::line_intersects_rect;
+    pub use super::rect_intersects_rect;
+}
+
+/// This module reexports the circular arc type, as well as the public facing
+/// arc collision detection functions.
+pub mod arc_collision {
+    pub use super::CircularArc;
+
+    pub use super::line_intersects_arc;
 }
 
 /// A bounding box is simply represented as a vector from the center of the
@@ -47,6 +56,93 @@ impl Rect {
     }
 }
 
+/// A section of the circumference of a circle. The arc spans `half_angle`
+/// radians on either side of `facing`, which is expected to be a unit vector.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CircularArc {
+    pub center: Point2,
+    pub radius: StorageTy,
+    pub facing: Vec2,
+    pub half_angle: StorageTy,
+}
+
+impl CircularArc {
+    /// Determines if the point `p` lies within the angular span of the arc,
+    /// regardless of its distance from the center.
+    fn spans(&self, p: Point2) -> bool {
+        let offset = p - self.center;
+        let norm = offset.norm();
+        if norm == 0.0 {
+            return false;
+        }
+        // Compare cosines rather than angles to avoid the trigonometry.
+        offset.dot(&self.facing) / norm >= self.half_angle.cos()
+    }
+}
+
+/// Determines if the line segment from `p1` to `p2` crosses the arc `arc`. If
+/// it does, the crossing point closest to `p1` is returned. A segment lying
+/// entirely within the arc's sector never crosses the circle, but is still
+/// considered to hit it, in which case `p1` is returned.
+///
+/// The segment is parameterized as `p1 + t * (p2 - p1)` for `t` in `[0, 1]`,
+/// and substituted into the equation of the arc's circle. This gives a
+/// quadratic in `t` whose roots are the points at which the infinite line
+/// crosses the circle. Roots outside of `[0, 1]` are not on the segment, and
+/// crossing points outside of the arc's angular span are not on the arc.
+pub fn line_intersects_arc(
+    p1: Point2,
+    p2: Point2,
+    arc: CircularArc,
+) -> Option<Point2> {
+    let d = p2 - p1;
+    let f = p1 - arc.center;
+
+    let radius_squared = arc.radius * arc.radius;
+    let a = d.dot(&d);
+    let b = 2.0 * f.dot(&d);
+    let c = f.dot(&f) - radius_squared;
+
+    // Both ends are inside the circle and within the arc's angular span, so
+    // the whole segment lies within the arc's sector.
+    let inside = |p: Point2| {
+        (p - arc.center).norm_squared() <= radius_squared && arc.spans(p)
+    };
+    if inside(p1) && inside(p2) {
+        return Some(p1);
+    }
+
+    // A degenerate segment can not cross anything.
+    if a == 0.0 {
+        return None;
+    }
+
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let root = discriminant.sqrt();
+    let t1 = (-b - root) / (2.0 * a);
+    let t2 = (-b + root) / (2.0 * a);
+
+    // `t1 <= t2`, so checking in this order finds the crossing closest to
+    // `p1` first.
+    [t1, t2].iter()
+        .filter(|&&t| (0.0..=1.0).contains(&t))
+        .map(|&t| p1 + d * t)
+        .find(|&p| arc.spans(p))
+}
+
+/// Determines if the rectangles `a` and `b` overlap. Rectangles that merely
+/// touch along an edge are considered to overlap.
+pub fn rect_intersects_rect(a: Rect, b: Rect) -> bool {
+    a.pos[0] <= b.pos[0] + b.width &&
+    b.pos[0] <= a.pos[0] + a.width &&
+    a.pos[1] <= b.pos[1] + b.height &&
+    b.pos[1] <= a.pos[1] + a.height
+}
+
 /// The three cases of possible orientations for triplets of points.
 #[derive(PartialEq)]
 pub enum TripletOrientation {
@@ -146,3 +242,75 @@ pub fn triplet_orientation(p: Point2, q: Point2, r: Point2) -> TripletOrientatio
         TripletOrientation::Counterclockwise
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A unit arc around the origin, spanning 45 degrees on either side of the
+    /// y-axis.
+    fn arc() -> CircularArc {
+        CircularArc {
+            center: Point2::origin(),
+            radius: 1.0,
+            facing: Vec2::y(),
+            half_angle: PI / 4.0,
+        }
+    }
+
+    /// Asserts that `contact` is a hit at `expected`, up to rounding.
+    fn assert_hit_at(contact: Option<Point2>, expected: Point2) {
+        let contact = contact.expect("The segment should hit the arc");
+        assert!(
+            (contact - expected).norm() < 1e-5,
+            "Expected a hit at {}, got {}",
+            expected,
+            contact,
+        );
+    }
+
+    #[test]
+    fn segment_crossing_arc_hits_closest_crossing() {
+        let contact = line_intersects_arc(
+            Point2::new(0.0, 2.0),
+            Point2::new(0.0, 0.5),
+            arc(),
+        );
+        assert_hit_at(contact, Point2::new(0.0, 1.0));
+    }
+
+    #[test]
+    fn segment_inside_arc_hits() {
+        let p1 = Point2::new(-0.1, 0.5);
+        let contact = line_intersects_arc(p1, Point2::new(0.1, 0.7), arc());
+        assert_eq!(contact, Some(p1));
+    }
+
+    #[test]
+    fn segment_outside_arc_angle_misses() {
+        // This crosses the circle, but below the arc.
+        let crossing = line_intersects_arc(
+            Point2::new(0.0, -2.0),
+            Point2::new(0.0, -0.5),
+            arc(),
+        );
+        assert_eq!(crossing, None);
+        // This lies inside the circle, but beside the arc.
+        let inside = line_intersects_arc(
+            Point2::new(0.5, 0.0),
+            Point2::new(0.7, -0.1),
+            arc(),
+        );
+        assert_eq!(inside, None);
+    }
+
+    #[test]
+    fn segment_tangent_to_arc_hits() {
+        let contact = line_intersects_arc(
+            Point2::new(-1.0, 1.0),
+            Point2::new(1.0, 1.0),
+            arc(),
+        );
+        assert_hit_at(contact, Point2::new(0.0, 1.0));
+    }
+}
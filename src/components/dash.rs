@@ -0,0 +1,58 @@
+//! This component holds the state of an entity's dash ability. Dashes are
+//! timed in real (unscaled) seconds, like invulnerability, so that slowing time
+//! down neither lengthens a dash nor its cooldown. The state is public so that
+//! a HUD can display it.
+use amethyst::ecs::prelude::{Component, DenseVecStorage};
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Dash {
+    /// The remaining real time of the dash in progress, if any.
+    pub active_for: f32,
+    /// The remaining real time of the invulnerability granted by the latest
+    /// dash. Unlike the invulnerability that follows a hit, it does not make
+    /// the player blink.
+    pub invulnerable_for: f32,
+    /// The remaining real time until the next dash is available.
+    pub cooldown_for: f32,
+    /// The total cooldown, in real seconds, after a dash is triggered.
+    pub cooldown: f32,
+    /// Whether the dash input was held on the previous update. Dashes trigger
+    /// when the input is first pressed, not while it is held.
+    pub was_held: bool,
+}
+
+impl Dash {
+    pub fn with_cooldown(cooldown: f32) -> Self {
+        Self {
+            cooldown,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_active(self) -> bool {
+        self.active_for > 0.0
+    }
+
+    pub fn is_invulnerable(self) -> bool {
+        self.invulnerable_for > 0.0
+    }
+
+    pub fn is_ready(self) -> bool {
+        self.cooldown_for <= 0.0
+    }
+
+    /// The fraction of the cooldown that has elapsed, from `0.0` right after a
+    /// dash to `1.0` once the next dash is available.
+    pub fn charge(self) -> f32 {
+        if self.cooldown <= 0.0 {
+            1.0
+        } else {
+            1.0 - (self.cooldown_for / self.cooldown).max(0.0).min(1.0)
+        }
+    }
+}
+
+impl Component for Dash {
+    // TODO: investigate storage types.
+    type Storage = DenseVecStorage<Self>;
+}
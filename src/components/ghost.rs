@@ -0,0 +1,15 @@
+//! This component marks the entities drawing a ghost, the translucent replay
+//! of a player from an earlier run. Ghosts are only drawn, and take no part in
+//! the simulation, so they can never collide with anything.
+use amethyst::ecs::prelude::{Component, DenseVecStorage};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Ghost {
+    /// The index of the recorded player this ghost follows.
+    pub index: usize,
+}
+
+impl Component for Ghost {
+    // TODO: investigate storage types.
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,50 @@
+//! This component holds the health of an entity, along with its post-hit
+//! invulnerability. Invulnerability is measured in real (unscaled) seconds, so
+//! that slowing time down does not stretch it out.
+use amethyst::ecs::prelude::{Component, DenseVecStorage};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+    /// The real time, in seconds, that the entity stays invulnerable after
+    /// taking damage.
+    pub invulnerability_time: f32,
+    /// The remaining real time, in seconds, of the current invulnerability.
+    pub invulnerable_for: f32,
+}
+
+impl Health {
+    pub fn new(max: f32, invulnerability_time: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            invulnerability_time,
+            invulnerable_for: 0.0,
+        }
+    }
+
+    pub fn is_invulnerable(self) -> bool {
+        self.invulnerable_for > 0.0
+    }
+
+    pub fn is_dead(self) -> bool {
+        self.current <= 0.0
+    }
+
+    /// Applies `damage` unless the entity is currently invulnerable, in which
+    /// case nothing happens. Returns `true` if the damage was applied.
+    pub fn damage(&mut self, damage: f32) -> bool {
+        if self.is_invulnerable() {
+            return false;
+        }
+        self.current = (self.current - damage).max(0.0);
+        self.invulnerable_for = self.invulnerability_time;
+        true
+    }
+}
+
+impl Component for Health {
+    // TODO: investigate storage types.
+    type Storage = DenseVecStorage<Self>;
+}
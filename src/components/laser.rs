@@ -1,15 +1,24 @@
 //! This component holds properties about a laser.
 use amethyst::ecs::prelude::{Component, DenseVecStorage};
 
+use crate::components::Polarity;
 use crate::vector::StorageTy;
 
+#[derive(Clone, Debug)]
 pub struct Laser {
+    /// The name of the archetype this laser was spawned from.
+    pub archetype: String,
     // TODO: is this idiomatic? I should probably be using amethyst's color
     // types.
     /// The color of the laser. This is currently applied as a tint over an all
     /// white sprite.
     pub color: (f32, f32, f32),
     pub len: StorageTy,
+    pub width: StorageTy,
+    /// The damage dealt to a player hit by this laser.
+    pub damage: f32,
+    /// Players of the same polarity absorb this laser instead of being hurt.
+    pub polarity: Polarity,
 }
 
 impl Component for Laser {
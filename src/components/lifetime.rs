@@ -0,0 +1,30 @@
+//! This component limits how long an entity may exist. The remaining lifetime
+//! is measured in scaled seconds, so that entities do not expire any faster
+//! while time is slowed down.
+use amethyst::ecs::prelude::{Component, DenseVecStorage};
+
+use crate::vector::StorageTy;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Lifetime {
+    /// The remaining lifetime of the entity, in scaled seconds.
+    pub remaining: StorageTy,
+}
+
+impl Lifetime {
+    pub fn new(seconds: StorageTy) -> Self {
+        Self {
+            remaining: seconds,
+        }
+    }
+
+    /// Returns `true` once the entity has outlived its lifetime.
+    pub fn is_expired(self) -> bool {
+        self.remaining <= 0.0
+    }
+}
+
+impl Component for Lifetime {
+    // TODO: investigate storage types.
+    type Storage = DenseVecStorage<Self>;
+}
@@ -1,11 +1,47 @@
 mod bounding_box;
 pub use bounding_box::BoundingBox;
 
+mod dash;
+pub use dash::Dash;
+
+mod ghost;
+pub use ghost::Ghost;
+
+mod health;
+pub use health::Health;
+
+mod physics;
+pub use physics::{Drag, ForceAccumulator, Mass, MaxSpeed};
+
+mod pickup;
+pub use pickup::{Pickup, PickupKind};
+
 mod player;
-pub use player::Player;
+pub use player::{Player, PlayerSlots};
 
 mod laser;
 pub use laser::Laser;
 
+mod lifetime;
+pub use lifetime::Lifetime;
+
+mod motion_model;
+pub use motion_model::{MotionKind, MotionModel};
+
+mod polarity;
+pub use polarity::{Polarity, PolarityConfig, Polarized};
+
+mod rewindable;
+pub use rewindable::Rewindable;
+
+mod shield;
+pub use shield::{Shield, ShieldConfig, ShieldMode};
+
+mod tether;
+pub use tether::Tether;
+
+mod time_domain;
+pub use time_domain::TimeDomain;
+
 mod relative_locomotor;
 pub use relative_locomotor::RelativeLocomotor;
@@ -0,0 +1,289 @@
+//! This component describes how an entity moves beyond simple constant
+//! velocity. The `RelativeMotionSystem` evaluates it each frame with the same
+//! time scaling as every other locomotor.
+//!
+//! Motion models come in two flavours. _Differential_ models (angular drift,
+//! homing and acceleration) describe how the velocity changes over time and are
+//! integrated step by step. _Parametric_ models (sinusoids, orbits and Bezier
+//! paths) describe the position directly as a function of elapsed time, which
+//! means they can never accumulate integration error.
+//!
+//! Parametric models are described in the local frame of the entity at the
+//! moment the model was attached. In this frame, `+y` points along the initial
+//! direction of travel and `+x` points to its right.
+use amethyst::ecs::prelude::{Component, DenseVecStorage};
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::RelativeLocomotor;
+use crate::util::prelude::*;
+use crate::vector::prelude::*;
+
+/// The configurable description of a motion model. This is what appears in
+/// the spawner configuration files.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum MotionKind {
+    /// Constant velocity. This is equivalent to having no motion model at all.
+    Linear,
+    /// Travel along the initial velocity while oscillating laterally.
+    Sinusoidal {
+        /// The maximum lateral displacement from the line of travel.
+        amplitude: StorageTy,
+        /// The number of full oscillations per (scaled) second.
+        frequency: StorageTy,
+    },
+    /// Constantly rotate the velocity, producing curving shots. Positive values
+    /// curve counterclockwise.
+    AngularDrift {
+        /// The rotation applied to the velocity, in radians per second.
+        angular_velocity: StorageTy,
+    },
+    /// Accelerate (or decelerate, if negative) along the current direction of
+    /// travel, keeping the speed within the given bounds.
+    Accelerate {
+        acceleration: StorageTy,
+        min_speed: StorageTy,
+        max_speed: StorageTy,
+    },
+    /// Steer towards the model's center, which is kept up to date with the
+    /// nearest living player's position. The speed of the entity is left
+    /// unchanged.
+    Homing {
+        /// The maximum rate at which the velocity turns, in radians per second.
+        turn_rate: StorageTy,
+    },
+    /// Orbit around the model's center, optionally moving towards or away from
+    /// it. A negative `radial_speed` produces an inward spiral.
+    Orbit {
+        /// The angular velocity around the center, in radians per second.
+        angular_velocity: StorageTy,
+        /// The rate at which the orbit radius changes.
+        radial_speed: StorageTy,
+    },
+    /// Follow a cubic Bezier curve starting at the entity's initial position.
+    /// After `duration` seconds the entity continues in a straight line along
+    /// the curve's final tangent.
+    Bezier {
+        /// The three remaining control points, in the local frame.
+        control_points: [(StorageTy, StorageTy); 3],
+        /// The time it takes to traverse the curve.
+        duration: StorageTy,
+    },
+}
+
+impl Default for MotionKind {
+    fn default() -> Self {
+        MotionKind::Linear
+    }
+}
+
+/// The motion model component. Along with the configured `MotionKind`, this
+/// holds the state captured at the moment the model was attached.
+#[derive(Clone, Debug)]
+pub struct MotionModel {
+    pub kind: MotionKind,
+    /// The amount of scaled time that has passed since the model was attached.
+    pub elapsed: StorageTy,
+    /// The position at the start of the last integration step, along with
+    /// the length of that step in scaled seconds. Verlet integration builds on
+    /// it, and it is kept apart from the locomotor's `old_pos`, which is only
+    /// meant for interpolation.
+    pub prev: Option<(Point2, StorageTy)>,
+    /// The entity's position when the model was attached.
+    origin: Point2,
+    /// The entity's velocity when the model was attached.
+    base_velocity: Vec2,
+    /// The point around which orbits are computed, or towards which homing
+    /// models steer.
+    center: Point2,
+}
+
+impl MotionModel {
+    /// Builds a `MotionModel` for an entity currently described by
+    /// `locomotor`. The `center` is only used by orbiting and homing models.
+    pub fn new(
+        kind: MotionKind,
+        locomotor: &RelativeLocomotor,
+        center: Point2,
+    ) -> Self {
+        Self {
+            kind,
+            elapsed: 0.0,
+            prev: None,
+            origin: locomotor.pos,
+            base_velocity: locomotor.velocity,
+            center,
+        }
+    }
+
+    /// Returns `true` if this model describes the position directly as a
+    /// function of time, rather than through its velocity.
+    pub fn is_parametric(&self) -> bool {
+        match self.kind {
+            MotionKind::Sinusoidal { .. }
+            | MotionKind::Orbit { .. }
+            | MotionKind::Bezier { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if this model steers towards a moving target.
+    pub fn is_homing(&self) -> bool {
+        match self.kind {
+            MotionKind::Homing { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Accounts for a change of `delta_v` made to the entity's velocity outside
+    /// of integration, such as an impulse. Verlet integration derives the
+    /// velocity from the previous position, so that position is moved instead.
+    pub fn shift_velocity(&mut self, delta_v: Vec2) {
+        if let Some((pos, h)) = self.prev.as_mut() {
+            *pos -= delta_v * *h;
+        }
+    }
+
+    /// Moves the point that homing models steer towards.
+    pub fn retarget(&mut self, target: Point2) {
+        self.center = target;
+    }
+
+    /// The acceleration of a differential model at the given position and
+    /// velocity. Parametric and linear models have no acceleration.
+    pub fn acceleration(&self, pos: Point2, velocity: Vec2) -> Vec2 {
+        match self.kind {
+            MotionKind::AngularDrift { angular_velocity } => {
+                // The derivative of a rotating vector is perpendicular to it.
+                Vec2::new(-velocity[1], velocity[0]) * angular_velocity
+            },
+            MotionKind::Homing { turn_rate } => {
+                // Turn left or right depending on which side of the velocity
+                // the target lies.
+                let to_target = self.center - pos;
+                let cross = velocity[0] * to_target[1]
+                    - velocity[1] * to_target[0];
+                let turn = if cross > 0.0 {
+                    turn_rate
+                } else if cross < 0.0 {
+                    -turn_rate
+                } else {
+                    0.0
+                };
+                Vec2::new(-velocity[1], velocity[0]) * turn
+            },
+            MotionKind::Accelerate { acceleration, min_speed, max_speed } => {
+                let speed = velocity.norm();
+                if speed == 0.0 {
+                    return Vec2::zeros();
+                }
+                // Stop accelerating once we have reached the bound we are
+                // heading towards.
+                if (acceleration > 0.0 && speed >= max_speed)
+                    || (acceleration < 0.0 && speed <= min_speed)
+                {
+                    Vec2::zeros()
+                } else {
+                    velocity / speed * acceleration
+                }
+            },
+            _ => Vec2::zeros(),
+        }
+    }
+
+    /// Keeps the velocity of a differential model within its bounds after an
+    /// integration step.
+    pub fn constrain_velocity(&self, velocity: Vec2) -> Vec2 {
+        match self.kind {
+            MotionKind::Accelerate { min_speed, max_speed, .. } => {
+                let speed = velocity.norm();
+                if speed == 0.0 {
+                    velocity
+                } else {
+                    velocity / speed * clamp(speed, min_speed, max_speed)
+                }
+            },
+            _ => velocity,
+        }
+    }
+
+    /// The position and velocity of a parametric model at time `t`, or `None`
+    /// if this model is not parametric.
+    pub fn evaluate(&self, t: StorageTy) -> Option<(Point2, Vec2)> {
+        match self.kind {
+            MotionKind::Sinusoidal { amplitude, frequency } => {
+                let (right, _) = self.frame();
+                let omega = 2.0 * PI * frequency;
+                let pos = self.origin
+                    + self.base_velocity * t
+                    + right * (amplitude * (omega * t).sin());
+                let velocity = self.base_velocity
+                    + right * (amplitude * omega * (omega * t).cos());
+                Some((pos, velocity))
+            },
+            MotionKind::Orbit { angular_velocity, radial_speed } => {
+                let offset = self.origin - self.center;
+                let radius_0 = offset.norm();
+                let theta_0 = offset[1].atan2(offset[0]);
+                // The radius may not shrink past the center.
+                let radius = (radius_0 + radial_speed * t).max(0.0);
+                let radial_speed =
+                    if radius > 0.0 { radial_speed } else { 0.0 };
+                let theta = theta_0 + angular_velocity * t;
+                let radial = Vec2::new(theta.cos(), theta.sin());
+                let tangent = Vec2::new(-radial[1], radial[0]);
+                let pos = self.center + radial * radius;
+                let velocity = radial * radial_speed
+                    + tangent * (radius * angular_velocity);
+                Some((pos, velocity))
+            },
+            MotionKind::Bezier { control_points, duration } => {
+                let p0 = self.origin;
+                let p1 = self.to_world(control_points[0]);
+                let p2 = self.to_world(control_points[1]);
+                let p3 = self.to_world(control_points[2]);
+                // Guard against a zero length curve.
+                let duration = duration.max(std::f32::EPSILON);
+
+                let s = clamp(t / duration, 0.0, 1.0);
+                let u = 1.0 - s;
+                let pos = p0.coords * (u * u * u)
+                    + p1.coords * (3.0 * u * u * s)
+                    + p2.coords * (3.0 * u * s * s)
+                    + p3.coords * (s * s * s);
+                // The derivative with respect to `s`, rescaled to be with
+                // respect to `t`.
+                let tangent = ((p1 - p0) * (3.0 * u * u)
+                    + (p2 - p1) * (6.0 * u * s)
+                    + (p3 - p2) * (3.0 * s * s)) / duration;
+
+                // Once the curve is finished, continue along the final tangent.
+                let overshoot = (t - duration).max(0.0);
+                let pos = Point2::new(pos[0], pos[1]);
+                Some((pos + tangent * overshoot, tangent))
+            },
+            _ => None,
+        }
+    }
+
+    /// The `(right, forward)` unit vectors of the local frame.
+    fn frame(&self) -> (Vec2, Vec2) {
+        let forward = if self.base_velocity == Vec2::zeros() {
+            Vec2::y()
+        } else {
+            self.base_velocity.normalize()
+        };
+        (Vec2::new(forward[1], -forward[0]), forward)
+    }
+
+    /// Converts a point in the local frame into world coordinates.
+    fn to_world(&self, (x, y): (StorageTy, StorageTy)) -> Point2 {
+        let (right, forward) = self.frame();
+        self.origin + right * x + forward * y
+    }
+}
+
+impl Component for MotionModel {
+    // TODO: investigate storage types.
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,84 @@
+//! These components describe how an entity responds to forces. Forces are
+//! gathered in a `ForceAccumulator` over the course of a frame by any system
+//! that wants to push the entity around (e.g. player input, explosions or
+//! gravity wells), and are integrated into the entity's velocity by the
+//! `PhysicsSystem`.
+//!
+//! Every component besides the `ForceAccumulator` is optional. Entities
+//! without a `Mass` have a mass of `1.0`, entities without `Drag` keep their
+//! velocity and entities without a `MaxSpeed` may go as fast as they are
+//! pushed.
+use amethyst::ecs::prelude::{Component, DenseVecStorage};
+
+use crate::vector::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Mass(pub StorageTy);
+
+impl Default for Mass {
+    fn default() -> Self {
+        Mass(1.0)
+    }
+}
+
+impl Component for Mass {
+    // TODO: investigate storage types.
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// A linear drag. Each second, the entity loses roughly this fraction of its
+/// velocity, regardless of its mass.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Drag(pub StorageTy);
+
+impl Component for Drag {
+    // TODO: investigate storage types.
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// The speed above which an entity's velocity is clamped after integration.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MaxSpeed(pub StorageTy);
+
+impl Component for MaxSpeed {
+    // TODO: investigate storage types.
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// The forces applied to an entity since the last integration step. The
+/// accumulator is cleared after every step.
+#[derive(Clone, Copy, Debug)]
+pub struct ForceAccumulator {
+    /// The sum of the continuous forces, which are scaled by the time step.
+    pub force: Vec2,
+    /// The sum of the instantaneous impulses, which are not.
+    pub impulse: Vec2,
+}
+
+impl Default for ForceAccumulator {
+    fn default() -> Self {
+        Self {
+            force: Vec2::zeros(),
+            impulse: Vec2::zeros(),
+        }
+    }
+}
+
+impl ForceAccumulator {
+    pub fn add_force(&mut self, force: Vec2) {
+        self.force += force;
+    }
+
+    pub fn add_impulse(&mut self, impulse: Vec2) {
+        self.impulse += impulse;
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl Component for ForceAccumulator {
+    // TODO: investigate storage types.
+    type Storage = DenseVecStorage<Self>;
+}
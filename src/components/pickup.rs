@@ -0,0 +1,36 @@
+//! This component marks an entity as a collectible. Pickups are collected when
+//! a living player's bounding box overlaps their own, at which point their
+//! effect is applied and they are removed.
+use amethyst::ecs::prelude::{Component, DenseVecStorage};
+
+use serde::{Deserialize, Serialize};
+
+/// The effect of a pickup on collection. Durations are in real (unscaled)
+/// seconds.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum PickupKind {
+    /// Adds to the run's score.
+    Score { points: u32 },
+    /// Heals the collecting player, up to their maximum health.
+    Health { amount: f32 },
+    /// Caps the time scale for a while, slowing the world down regardless of
+    /// input.
+    SlowTime { duration: f32, time_scale: f32 },
+    /// Refills part of the focus meter, as a fraction of the full meter.
+    Focus { amount: f32 },
+    /// Raises the collecting player's shield all the way around them for a
+    /// while, without draining energy.
+    Shield { duration: f32 },
+    /// Removes every laser in the world.
+    Bomb,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Pickup {
+    pub kind: PickupKind,
+}
+
+impl Component for Pickup {
+    // TODO: investigate storage types.
+    type Storage = DenseVecStorage<Self>;
+}
@@ -10,32 +10,106 @@ use amethyst::ecs::prelude::{Component, DenseVecStorage};
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Default, Deserialize, Serialize)]
+use crate::analog::AnalogConfig;
+use crate::components::{PolarityConfig, ShieldConfig};
+
+#[derive(Clone, Default, Deserialize, Serialize)]
 pub struct PlayerConfig {
+    /// The player's `MaxSpeed`.
     pub max_speed: f32,
+    /// The acceleration given by full input, regardless of the player's mass.
     pub acceleration: f32,
+    /// The player's `Drag`.
     pub deceleration: f32,
+    /// The response of the movement input.
+    pub analog: AnalogConfig,
+    /// The player's starting health.
+    pub max_health: f32,
+    /// The real time, in seconds, that the player is invulnerable after being
+    /// hit.
+    pub invulnerability_time: f32,
+    /// The number of times per real second that the player blinks while
+    /// invulnerable.
+    pub blink_frequency: f32,
+    /// The speed of the player at the start of a dash. This may exceed
+    /// `max_speed`.
+    pub dash_speed: f32,
+    /// The real time, in seconds, for which `max_speed` is not enforced after a
+    /// dash starts.
+    pub dash_duration: f32,
+    /// The real time, in seconds, between dashes.
+    pub dash_cooldown: f32,
+    /// The real time, in seconds, that the player is invulnerable after a dash
+    /// starts.
+    pub dash_invulnerability: f32,
+    /// The player's shield.
+    pub shield: ShieldConfig,
+    /// The player's polarity and absorption meter.
+    pub polarity: PolarityConfig,
+    /// The name of the time channel the player moves in, if not the world's.
+    #[serde(default)]
+    pub time_domain: Option<String>,
+}
+
+/// The properties that distinguish one local player from another. Unlike the
+/// `PlayerConfig`, which is shared by every player, each player has their own
+/// slot.
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct PlayerSlot {
+    /// The prefix of this player's input bindings. For example, a player with
+    /// the prefix `"p1"` moves with the `"p1_x_in"` and `"p1_y_in"` axes.
+    pub bindings: String,
+    /// The color of the player, applied as a tint over their sprite.
+    pub color: (f32, f32, f32),
+    /// The vector from the center of the player to the top right corner of
+    /// their bounding box.
+    pub hitbox: (f32, f32),
+}
+
+/// The list of players taking part in a game.
+// TODO: hopefully remove the `Default` derivation pending this issue:
+// https://github.com/amethyst/amethyst/issues/1954
+#[derive(Default, Deserialize, Serialize)]
+pub struct PlayerSlots {
+    pub players: Vec<PlayerSlot>,
+}
+
+impl PlayerSlots {
+    /// Reads the list of players from the RON file at `path`.
+    pub fn from_config_path(
+        path: impl AsRef<Path>
+    ) -> Result<Self, ConfigError> {
+        // TODO: hopefully change this to just call load pending this issue:
+        // https://github.com/amethyst/amethyst/issues/1954
+        Self::load_no_fallback(path)
+    }
 }
 
-// TODO: is this idiomatic for singleton entities like the player?
 pub struct Player {
     pub config: PlayerConfig,
+    pub slot: PlayerSlot,
 }
 
 impl Player {
-    pub fn from_config(config: impl Into<PlayerConfig>) -> Self {
+    pub fn new(config: impl Into<PlayerConfig>, slot: PlayerSlot) -> Self {
         Self {
             config: config.into(),
+            slot,
         }
     }
 
-    /// Builds a `Player` by reading the RON file at `path`.
-    pub fn from_config_path(
+    /// Reads the shared player configuration from the RON file at `path`.
+    pub fn config_from_path(
         path: impl AsRef<Path>
-    ) -> Result<Self, ConfigError> {
+    ) -> Result<PlayerConfig, ConfigError> {
         // TODO: hopefully change this to just call load pending this issue:
         // https://github.com/amethyst/amethyst/issues/1954
-        PlayerConfig::load_no_fallback(path).map(Self::from_config)
+        PlayerConfig::load_no_fallback(path)
+    }
+
+    /// The name of this player's binding for the input `name`, e.g. `"x_in"`.
+    pub fn binding(&self, name: &str) -> String {
+        format!("{}_{}", self.slot.bindings, name)
     }
 }
 
@@ -0,0 +1,103 @@
+//! This module holds the colour polarity mechanic. Lasers are spawned with the
+//! polarity of their archetype, and players may switch between the light and
+//! dark polarities at will. A laser hitting a player of the same polarity is
+//! absorbed instead of dealing damage, charging the player's meter. Neutral
+//! lasers can never be absorbed.
+use amethyst::ecs::prelude::{Component, DenseVecStorage};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub enum Polarity {
+    Neutral,
+    Light,
+    Dark,
+}
+
+impl Default for Polarity {
+    fn default() -> Self {
+        Polarity::Neutral
+    }
+}
+
+impl Polarity {
+    /// The polarity a player switches to from this one. Neutral is never
+    /// switched to.
+    pub fn toggled(self) -> Self {
+        match self {
+            Polarity::Light => Polarity::Dark,
+            Polarity::Dark | Polarity::Neutral => Polarity::Light,
+        }
+    }
+}
+
+/// The configurable properties of a player's polarity.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct PolarityConfig {
+    /// The charge held by a full meter. Each absorbed laser adds its damage to
+    /// the meter.
+    pub max_charge: f32,
+    /// The colors multiplied into the player's own color while they have each
+    /// polarity.
+    pub light_color: (f32, f32, f32),
+    pub dark_color: (f32, f32, f32),
+}
+
+impl PolarityConfig {
+    /// The color multiplied into a player's own color while they have
+    /// `polarity`.
+    pub fn color(self, polarity: Polarity) -> (f32, f32, f32) {
+        match polarity {
+            Polarity::Light => self.light_color,
+            Polarity::Dark => self.dark_color,
+            Polarity::Neutral => (1.0, 1.0, 1.0),
+        }
+    }
+}
+
+/// The polarity of an entity that can absorb lasers, along with its meter.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Polarized {
+    pub config: PolarityConfig,
+    pub polarity: Polarity,
+    /// The charge gathered by absorbing lasers.
+    pub charge: f32,
+    /// Whether the polarity input was held on the previous update. The
+    /// polarity switches when the input is first pressed, not while it is held.
+    pub was_held: bool,
+}
+
+impl Polarized {
+    /// Builds a `Polarized` with the light polarity and an empty meter.
+    pub fn from_config(config: PolarityConfig) -> Self {
+        Self {
+            config,
+            polarity: Polarity::Light,
+            ..Self::default()
+        }
+    }
+
+    /// Determines if a laser with the polarity `other` is absorbed.
+    pub fn absorbs(self, other: Polarity) -> bool {
+        other != Polarity::Neutral && other == self.polarity
+    }
+
+    /// Adds `amount` to the meter, up to its maximum.
+    pub fn absorb(&mut self, amount: f32) {
+        self.charge = (self.charge + amount).min(self.config.max_charge);
+    }
+
+    /// The fraction of the meter that is currently full.
+    pub fn fill(self) -> f32 {
+        if self.config.max_charge <= 0.0 {
+            0.0
+        } else {
+            (self.charge / self.config.max_charge).max(0.0).min(1.0)
+        }
+    }
+}
+
+impl Component for Polarized {
+    // TODO: investigate storage types.
+    type Storage = DenseVecStorage<Self>;
+}
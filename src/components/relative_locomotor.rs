@@ -7,7 +7,7 @@ use amethyst::ecs::{Component, DenseVecStorage};
 
 use crate::vector::prelude::*;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct RelativeLocomotor {
     pub old_pos: Point2,
     pub pos: Point2,
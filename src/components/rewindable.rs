@@ -0,0 +1,13 @@
+//! This component gives an entity a stable identity in the rewind history.
+//! Entity ids are reused once an entity is deleted, so the history refers to
+//! entities by this id instead, which allows deleted entities to be rebuilt
+//! when the world is rewound past their deletion.
+use amethyst::ecs::prelude::{Component, DenseVecStorage};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rewindable(pub u64);
+
+impl Component for Rewindable {
+    // TODO: investigate storage types.
+    type Storage = DenseVecStorage<Self>;
+}
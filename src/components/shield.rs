@@ -0,0 +1,93 @@
+//! This component holds the state of an entity's shield. While the shield is
+//! raised, it projects an arc in the direction the entity is facing that
+//! deflects or destroys incoming lasers, at the cost of draining an energy
+//! meter. Energy drains and regenerates in real (unscaled) time.
+use amethyst::ecs::prelude::{Component, DenseVecStorage};
+
+use serde::{Deserialize, Serialize};
+
+use crate::vector::prelude::*;
+
+/// What happens to a laser that touches a raised shield.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum ShieldMode {
+    /// The laser bounces off the shield.
+    Reflect,
+    /// The laser is removed.
+    Destroy,
+}
+
+impl Default for ShieldMode {
+    fn default() -> Self {
+        ShieldMode::Reflect
+    }
+}
+
+// TODO: hopefully remove the `Default` derivation pending this issue:
+// https://github.com/amethyst/amethyst/issues/1954
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct ShieldConfig {
+    pub max_energy: f32,
+    /// The energy used per real second while the shield is raised.
+    pub drain_rate: f32,
+    /// The energy regained per real second while the shield is lowered.
+    pub regen_rate: f32,
+    /// The distance from the center of the entity to the shield's arc.
+    pub radius: StorageTy,
+    /// The angle covered on either side of the facing direction, in radians.
+    pub half_angle: StorageTy,
+    pub mode: ShieldMode,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Shield {
+    pub config: ShieldConfig,
+    pub energy: f32,
+    pub raised: bool,
+    /// The unit vector pointing towards the middle of the shield's arc.
+    pub facing: Vec2,
+    /// The remaining real time for which the shield is boosted. A boosted
+    /// shield is raised all the way around the entity without any input, and
+    /// does not drain energy.
+    pub boosted_for: f32,
+}
+
+impl Shield {
+    pub fn from_config(config: ShieldConfig) -> Self {
+        Self {
+            config,
+            energy: config.max_energy,
+            raised: false,
+            facing: Vec2::y(),
+            boosted_for: 0.0,
+        }
+    }
+
+    pub fn is_boosted(self) -> bool {
+        self.boosted_for > 0.0
+    }
+
+    /// The angle covered by the shield on either side of its facing direction.
+    /// Boosted shields cover every direction.
+    pub fn half_angle(self) -> StorageTy {
+        if self.is_boosted() {
+            PI
+        } else {
+            self.config.half_angle
+        }
+    }
+
+    /// The fraction of the energy meter that is currently full.
+    pub fn charge(self) -> f32 {
+        if self.config.max_energy <= 0.0 {
+            0.0
+        } else {
+            self.energy / self.config.max_energy
+        }
+    }
+}
+
+impl Component for Shield {
+    // TODO: investigate storage types.
+    type Storage = DenseVecStorage<Self>;
+}
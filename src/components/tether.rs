@@ -0,0 +1,24 @@
+//! This component turns a laser into a fence stretched between two anchor
+//! entities. The anchors move on their own (each has its own
+//! `RelativeLocomotor`), and the `TetherSystem` keeps the laser spanning the
+//! segment between them.
+use amethyst::ecs::prelude::{Component, DenseVecStorage, Entity};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Tether {
+    /// The two entities between which the laser is stretched.
+    pub anchors: [Entity; 2],
+}
+
+impl Tether {
+    pub fn between(a: Entity, b: Entity) -> Self {
+        Self {
+            anchors: [a, b],
+        }
+    }
+}
+
+impl Component for Tether {
+    // TODO: investigate storage types.
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,12 @@
+//! This component places an entity in a named time scaling channel, so that it
+//! may move faster or slower than the rest of the world. Entities without a
+//! `TimeDomain` are in the `"world"` channel, which follows the `TimeScale`.
+use amethyst::ecs::prelude::{Component, DenseVecStorage};
+
+#[derive(Clone, Debug)]
+pub struct TimeDomain(pub String);
+
+impl Component for TimeDomain {
+    // TODO: investigate storage types.
+    type Storage = DenseVecStorage<Self>;
+}
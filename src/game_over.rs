@@ -0,0 +1,154 @@
+//! This module holds the state entered once every player has died. The world is
+//! frozen underneath a summary of the run until the player asks to restart, at
+//! which point the world is torn down and rebuilt before returning to the main
+//! gameplay state.
+use std::path::{Path, PathBuf};
+
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    ecs::{Entity, Join},
+    input::InputEvent,
+    prelude::*,
+    ui::{get_default_font, Anchor, FontAsset, LineMode, UiText, UiTransform},
+};
+
+use crate::components::{Laser, Lifetime, Player};
+use crate::lase::initialize_players;
+use crate::resources::{
+    Focus,
+    History,
+    KillCamRecording,
+    RewindMeter,
+    RunStats,
+    SlowTime,
+    TimeChannels,
+    TimeScale,
+};
+
+/// The state shown after every player's health reaches zero. It is pushed on
+/// top of the main gameplay state, and pops itself once the world has been
+/// reset.
+pub struct GameOver {
+    config_path: PathBuf,
+    /// The UI entity displaying the run summary.
+    summary: Option<Entity>,
+}
+
+impl GameOver {
+    pub fn with_config_path(config_path: PathBuf) -> Self {
+        Self {
+            config_path,
+            summary: None,
+        }
+    }
+}
+
+impl SimpleState for GameOver {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let stats = *data.world.read_resource::<RunStats>();
+
+        let font = {
+            let loader = data.world.read_resource::<Loader>();
+            let font_storage =
+                data.world.read_resource::<AssetStorage<FontAsset>>();
+            get_default_font(&loader, &font_storage)
+        };
+
+        let summary = format!(
+            "GAME OVER\n\n\
+             survived {:.1}s ({:.1}s of game time)\n\
+             lasers fired: {}\n\
+             hits taken: {}\n\
+             lasers absorbed: {}\n\
+             score: {}\n\n\
+             press enter to restart",
+            stats.real_time,
+            stats.scaled_time,
+            stats.lasers_spawned,
+            stats.hits_taken,
+            stats.lasers_absorbed,
+            stats.score,
+        );
+        let mut text = UiText::new(font, summary, [1.0, 1.0, 1.0, 1.0], 24.0);
+        text.line_mode = LineMode::Wrap;
+
+        let transform = UiTransform::new(
+            "game_over_summary".to_string(),
+            Anchor::Middle,
+            Anchor::Middle,
+            0.0,
+            0.0,
+            1.0,
+            480.0,
+            240.0,
+        );
+
+        self.summary = Some(
+            data.world
+                .create_entity()
+                .with(transform)
+                .with(text)
+                .build()
+        );
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        if let Some(summary) = self.summary.take() {
+            // Panic in case of entity deletion failure.
+            data.world.delete_entity(summary)
+                .expect("The deletion of an entity failed?");
+        }
+    }
+
+    fn handle_event(
+        &mut self,
+        data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        match event {
+            StateEvent::Input(InputEvent::ActionPressed(ref action))
+                if action == "restart" =>
+            {
+                reset_world(data.world, &self.config_path);
+                Trans::Pop
+            },
+            _ => Trans::None,
+        }
+    }
+}
+
+/// Tears down everything left over from the previous run and creates fresh
+/// players.
+fn reset_world(world: &mut World, config_path: &Path) {
+    // Every laser, everything with a limited lifetime (e.g. fence anchors and
+    // pickups), and the old players.
+    let doomed: Vec<Entity> = {
+        let entities = world.entities();
+        let lasers = world.read_storage::<Laser>();
+        let lifetimes = world.read_storage::<Lifetime>();
+        let players = world.read_storage::<Player>();
+        (&*entities).join()
+            .filter(|&entity| {
+                lasers.contains(entity)
+                    || lifetimes.contains(entity)
+                    || players.contains(entity)
+            })
+            .collect()
+    };
+    // Panic in case of entity deletion failure.
+    world.delete_entities(&doomed)
+        .expect("The deletion of an entity failed?");
+
+    *world.write_resource::<TimeScale>() = TimeScale::default();
+    *world.write_resource::<TimeChannels>() = TimeChannels::default();
+    *world.write_resource::<RewindMeter>() = RewindMeter::default();
+    *world.write_resource::<Focus>() = Focus::default();
+    // Nothing with an id is left, so ids can start over. This keeps the ids
+    // of every run the same as in its replay.
+    *world.write_resource::<History>() = History::default();
+    world.write_resource::<KillCamRecording>().clear();
+    *world.write_resource::<RunStats>() = RunStats::default();
+    *world.write_resource::<SlowTime>() = SlowTime::default();
+
+    initialize_players(world, config_path);
+}
@@ -0,0 +1,136 @@
+//! This module sets up ghosts, the translucent replays of players from an
+//! earlier run. By default the ghosts follow the personal best, the run
+//! survived the longest so far. The live run is seeded like the ghost's run,
+//! so that both face the same lasers for as long as the players' paths stay
+//! close.
+//!
+//! Ghosts follow the player positions recorded in a `Replay`, and are moved
+//! along by the `GhostSystem`.
+use std::path::Path;
+
+use amethyst::{
+    config::{Config, ConfigError},
+    core::{transform::Transform, Hidden},
+    ecs::{Entity, Join},
+    prelude::*,
+    renderer::{
+        palette::Srgba,
+        resources::Tint,
+        transparent::Transparent,
+        SpriteRender,
+    },
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::{Ghost, Health, Player, RelativeLocomotor};
+use crate::replay::Replay;
+use crate::resources::{GhostRun, SpriteMap};
+use crate::vector::prelude::*;
+
+// TODO: hopefully remove the `Default` derivation pending this issue:
+// https://github.com/amethyst/amethyst/issues/1954
+#[derive(Clone, Copy, Default, Deserialize, Serialize)]
+pub struct GhostConfig {
+    pub enabled: bool,
+    pub color: (f32, f32, f32),
+    /// The opacity of the ghosts.
+    pub alpha: f32,
+}
+
+impl GhostConfig {
+    /// Reads the ghost configuration from the RON file at `path`.
+    pub fn from_config_path(
+        path: impl AsRef<Path>
+    ) -> Result<Self, ConfigError> {
+        // TODO: hopefully change this to just call load pending this issue:
+        // https://github.com/amethyst/amethyst/issues/1954
+        Self::load_no_fallback(path)
+    }
+}
+
+/// The position of every player, or `None` for dead players. Players are
+/// ordered by their bindings, so that they are recorded in the same order in
+/// every run.
+pub fn player_positions(
+    world: &World,
+) -> Vec<Option<(StorageTy, StorageTy)>> {
+    let players = world.read_storage::<Player>();
+    let locomotors = world.read_storage::<RelativeLocomotor>();
+    let healths = world.read_storage::<Health>();
+
+    let mut positions: Vec<_> = (&players, &locomotors, (&healths).maybe())
+        .join()
+        .map(|(player, locomotor, health)| {
+            let alive = health.map_or(true, |health| !health.is_dead());
+            let pos = if alive {
+                Some((locomotor.pos[0], locomotor.pos[1]))
+            } else {
+                None
+            };
+            (player.slot.bindings.clone(), pos)
+        })
+        .collect();
+    positions.sort_by(|(a, _), (b, _)| a.cmp(b));
+    positions.into_iter().map(|(_, pos)| pos).collect()
+}
+
+/// Replaces the ghosts in `world` with ones following the players recorded in
+/// `replay`. Without a replay, the ghosts are simply removed. Whether the
+/// ghosts are visible carries over from the previous run.
+pub fn spawn_ghosts(
+    world: &mut World,
+    config: GhostConfig,
+    replay: Option<&Replay>,
+) {
+    let old_ghosts: Vec<Entity> = {
+        let entities = world.entities();
+        let ghosts = world.read_storage::<Ghost>();
+        (&*entities, &ghosts).join()
+            .map(|(entity, _)| entity)
+            .collect()
+    };
+    // Panic in case of entity deletion failure.
+    world.delete_entities(&old_ghosts)
+        .expect("The deletion of an entity failed?");
+
+    let positions = replay.map_or_else(Vec::new, |replay| {
+        replay.positions.iter()
+            .map(|players| {
+                players.iter()
+                    .map(|pos| pos.map(|(x, y)| Point2::new(x, y)))
+                    .collect()
+            })
+            .collect()
+    });
+    let visible = world.try_fetch::<GhostRun>()
+        .map_or(true, |ghost_run| ghost_run.visible);
+    let ghost_run = GhostRun::new(positions, visible);
+
+    let sprite_sheet = {
+        let sprite_map = world.read_resource::<SpriteMap>();
+        // TODO: Make this type checked by having some kind of enum system for
+        // hardcoded texture ids?
+        sprite_map.get("player").unwrap()
+    };
+    let (r, g, b) = config.color;
+
+    for index in 0..ghost_run.players() {
+        let sprite_render = SpriteRender {
+            sprite_sheet: sprite_sheet.clone(),
+            sprite_number: 0,
+        };
+
+        // Ghosts are placed by the `GhostSystem`, and stay hidden until then.
+        world.create_entity()
+            .with(sprite_render)
+            .with(Transform::default())
+            .with(Tint(Srgba::new(r, g, b, config.alpha)))
+            .with(Transparent)
+            .with(Hidden)
+            .with(Ghost { index })
+            .build();
+    }
+
+    world.insert(ghost_run);
+}
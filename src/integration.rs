@@ -0,0 +1,229 @@
+//! This module holds the numerical integrators used to advance differential
+//! motion. Every integrator advances a position and velocity by a single step
+//! of length `h`, given a function computing the acceleration at any position
+//! and velocity.
+//!
+//! Explicit Euler is the cheapest, but drifts noticeably on curved paths.
+//! Semi-implicit Euler costs the same and is far more stable. Position Verlet
+//! is second order and uses the position from the previous step instead of
+//! the velocity. RK4 is the most accurate, at the cost of evaluating the
+//! acceleration four times per step.
+
+use serde::{Deserialize, Serialize};
+
+use crate::vector::prelude::*;
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub enum Integrator {
+    ExplicitEuler,
+    SemiImplicitEuler,
+    Verlet,
+    Rk4,
+}
+
+impl Default for Integrator {
+    fn default() -> Self {
+        Integrator::SemiImplicitEuler
+    }
+}
+
+impl Integrator {
+    /// Advances `pos` and `velocity` by a step of length `h`. Verlet
+    /// integration needs the position at the start of the previous step along
+    /// with that step's length, as `prev`. Without it, or if the previous step
+    /// was empty, Verlet falls back to a velocity based first step. Otherwise
+    /// Verlet integration only uses `velocity` to evaluate the acceleration,
+    /// so changes to the velocity must be made by moving the previous position
+    /// instead.
+    pub fn step<F>(
+        self,
+        pos: Point2,
+        velocity: Vec2,
+        prev: Option<(Point2, StorageTy)>,
+        h: StorageTy,
+        acceleration: F,
+    ) -> (Point2, Vec2)
+    where
+        F: Fn(Point2, Vec2) -> Vec2,
+    {
+        if h <= 0.0 {
+            return (pos, velocity);
+        }
+
+        match self {
+            Integrator::ExplicitEuler => {
+                let a = acceleration(pos, velocity);
+                (pos + velocity * h, velocity + a * h)
+            },
+            Integrator::SemiImplicitEuler => {
+                let velocity = velocity + acceleration(pos, velocity) * h;
+                (pos + velocity * h, velocity)
+            },
+            Integrator::Verlet => {
+                let a = acceleration(pos, velocity);
+                let new_pos = match prev {
+                    // Time corrected Verlet, since the step length changes
+                    // along with the time scale.
+                    Some((prev_pos, prev_h)) if prev_h > 0.0 => {
+                        pos + (pos - prev_pos) * (h / prev_h)
+                            + a * (h * (h + prev_h) * 0.5)
+                    },
+                    _ => pos + velocity * h + a * (h * h * 0.5),
+                };
+                (new_pos, (new_pos - pos) / h)
+            },
+            Integrator::Rk4 => {
+                let half_h = h * 0.5;
+
+                let k1_p = velocity;
+                let k1_v = acceleration(pos, velocity);
+
+                let k2_p = velocity + k1_v * half_h;
+                let k2_v = acceleration(pos + k1_p * half_h, k2_p);
+
+                let k3_p = velocity + k2_v * half_h;
+                let k3_v = acceleration(pos + k2_p * half_h, k3_p);
+
+                let k4_p = velocity + k3_v * h;
+                let k4_v = acceleration(pos + k3_p * h, k4_p);
+
+                let sixth_h = h / 6.0;
+                let dp = k1_p + k2_p * 2.0 + k3_p * 2.0 + k4_p;
+                let dv = k1_v + k2_v * 2.0 + k3_v * 2.0 + k4_v;
+                (pos + dp * sixth_h, velocity + dv * sixth_h)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTEGRATORS: [Integrator; 4] = [
+        Integrator::ExplicitEuler,
+        Integrator::SemiImplicitEuler,
+        Integrator::Verlet,
+        Integrator::Rk4,
+    ];
+
+    /// Runs `integrator` over the given step lengths, threading the previous
+    /// position through like the `RelativeMotionSystem` does.
+    fn integrate<F>(
+        integrator: Integrator,
+        mut pos: Point2,
+        mut velocity: Vec2,
+        steps: impl IntoIterator<Item = StorageTy>,
+        acceleration: F,
+    ) -> (Point2, Vec2)
+    where
+        F: Fn(Point2, Vec2) -> Vec2,
+    {
+        let mut prev = None;
+        for h in steps {
+            let (new_pos, new_velocity) =
+                integrator.step(pos, velocity, prev, h, &acceleration);
+            prev = Some((pos, h));
+            pos = new_pos;
+            velocity = new_velocity;
+        }
+        (pos, velocity)
+    }
+
+    #[test]
+    fn constant_acceleration() {
+        // A projectile launched sideways lands at `(1, -5)` after a second.
+        let gravity = Vec2::new(0.0, -10.0);
+        let expected = Point2::new(1.0, -5.0);
+        for &integrator in INTEGRATORS.iter() {
+            let (pos, _) = integrate(
+                integrator,
+                Point2::origin(),
+                Vec2::x(),
+                (0..100).map(|_| 0.01),
+                |_, _| gravity,
+            );
+            // The Euler integrators are first order, and are off by half of
+            // the last step's change in position.
+            let tolerance = match integrator {
+                Integrator::ExplicitEuler
+                | Integrator::SemiImplicitEuler => 0.1,
+                _ => 1e-3,
+            };
+            let error = (pos - expected).norm();
+            assert!(error < tolerance, "{:?} is off by {}", integrator, error);
+        }
+    }
+
+    #[test]
+    fn verlet_corrects_for_changing_step_lengths() {
+        // Verlet is exact under constant acceleration, even as the time scale
+        // changes the length of each step.
+        let gravity = Vec2::new(0.0, -10.0);
+        let steps = (0..100).map(|i| if i % 2 == 0 { 0.005 } else { 0.015 });
+        let (pos, _) = integrate(
+            Integrator::Verlet,
+            Point2::origin(),
+            Vec2::x(),
+            steps,
+            |_, _| gravity,
+        );
+        assert!((pos - Point2::new(1.0, -5.0)).norm() < 1e-3);
+    }
+
+    #[test]
+    fn verlet_applies_impulses_through_previous_position() {
+        let (pos, velocity) = integrate(
+            Integrator::Verlet,
+            Point2::origin(),
+            Vec2::x(),
+            (0..10).map(|_| 0.1),
+            |_, _| Vec2::zeros(),
+        );
+        // Knock the entity upwards by moving the previous position.
+        let delta_v = Vec2::y();
+        let prev = Some((pos - (velocity + delta_v) * 0.1, 0.1));
+        let (_, velocity) = Integrator::Verlet.step(
+            pos,
+            velocity,
+            prev,
+            0.1,
+            |_, _| Vec2::zeros(),
+        );
+        assert!((velocity - Vec2::new(1.0, 1.0)).norm() < 1e-4);
+    }
+
+    #[test]
+    fn circular_orbit() {
+        // A unit circular orbit returns to its start after a full period.
+        let period = 2.0 * PI;
+        let steps = 1000;
+        let h = period / steps as StorageTy;
+        let start = Point2::new(1.0, 0.0);
+        for &integrator in INTEGRATORS.iter() {
+            let (pos, _) = integrate(
+                integrator,
+                start,
+                Vec2::y(),
+                (0..steps).map(|_| h),
+                |p, _| -p.coords,
+            );
+            let radius = pos.coords.norm();
+            match integrator {
+                // Explicit Euler gains energy, spiralling outwards.
+                Integrator::ExplicitEuler => {
+                    assert!(radius > 1.01, "The orbit did not drift");
+                },
+                _ => {
+                    let error = (pos - start).norm();
+                    assert!(
+                        error < 0.01,
+                        "{:?} is off by {}",
+                        integrator,
+                        error,
+                    );
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! This module holds the kill-cam, the state entered once every player has
+//! died. It replays the last few seconds of the `KillCamRecording` in slow
+//! motion, with the laser that dealt the lethal hit highlighted, before moving
+//! on to the game over screen.
+//!
+//! The replay is drawn with its own entities, while the frozen world is hidden
+//! underneath it. No gameplay systems run in the meantime, since the
+//! simulation only ticks while the main gameplay state is active.
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use amethyst::{
+    config::{Config, ConfigError},
+    core::{Hidden, Time, Transform},
+    ecs::{Entity, Join},
+    input::InputEvent,
+    prelude::*,
+    renderer::{palette::Srgba, resources::Tint, SpriteRender},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game_over::GameOver;
+use crate::resources::{KillCamRecording, RecordedFrame, RecordedSprite};
+
+// TODO: hopefully remove the `Default` derivation pending this issue:
+// https://github.com/amethyst/amethyst/issues/1954
+#[derive(Clone, Copy, Default, Deserialize, Serialize)]
+pub struct KillCamConfig {
+    /// How much of the end of the run is recorded, in real seconds. The
+    /// kill-cam is skipped entirely when this is zero.
+    pub duration: f32,
+    /// The speed of the replay, relative to the recorded time.
+    pub playback_speed: f32,
+    /// How long the final frame is held, in real seconds.
+    pub hold: f32,
+    /// The color of the laser that dealt the lethal hit.
+    pub highlight_color: (f32, f32, f32),
+    /// The factor by which the colors of everything else are dimmed.
+    pub dim: f32,
+}
+
+impl KillCamConfig {
+    /// Reads the kill-cam configuration from the RON file at `path`.
+    pub fn from_config_path(
+        path: impl AsRef<Path>
+    ) -> Result<Self, ConfigError> {
+        // TODO: hopefully change this to just call load pending this issue:
+        // https://github.com/amethyst/amethyst/issues/1954
+        Self::load_no_fallback(path)
+    }
+}
+
+/// The state replaying the end of the run. It is pushed on top of the main
+/// gameplay state, and switches to the `GameOver` state once the replay is over
+/// or skipped.
+pub struct KillCam {
+    config_path: PathBuf,
+    config: KillCamConfig,
+    frames: Vec<RecordedFrame>,
+    lethal: Option<u64>,
+    /// The length of a recorded frame, in seconds.
+    step: f32,
+    /// The real time spent replaying so far.
+    elapsed: f32,
+    /// The replay entities, by the `Rewindable` id of the entity they stand in
+    /// for.
+    replayed: HashMap<u64, Entity>,
+    /// The entities of the frozen world hidden during the replay.
+    hidden: Vec<Entity>,
+}
+
+impl KillCam {
+    pub fn with_config_path(config_path: PathBuf) -> Self {
+        Self {
+            config_path,
+            config: KillCamConfig::default(),
+            frames: Vec::new(),
+            lethal: None,
+            step: 0.0,
+            elapsed: 0.0,
+            replayed: HashMap::new(),
+            hidden: Vec::new(),
+        }
+    }
+
+    /// The state to move on to once the replay is over.
+    fn game_over(&self) -> SimpleTrans {
+        Trans::Switch(Box::new(GameOver::with_config_path(
+            self.config_path.clone(),
+        )))
+    }
+
+    /// The color a recorded entity is drawn with.
+    fn tint(&self, record: &RecordedSprite) -> Tint {
+        if self.lethal == Some(record.id) {
+            let (r, g, b) = self.config.highlight_color;
+            return Tint(Srgba::new(r, g, b, 1.0));
+        }
+
+        let dim = self.config.dim;
+        let color = record.tint.as_ref().map_or(
+            Srgba::new(1.0, 1.0, 1.0, 1.0),
+            |tint| tint.0,
+        );
+        Tint(Srgba::new(
+            color.color.red * dim,
+            color.color.green * dim,
+            color.color.blue * dim,
+            color.alpha,
+        ))
+    }
+
+    /// Draws the frame at `index`, creating and deleting replay entities as
+    /// recorded entities come and go.
+    fn show_frame(&mut self, world: &mut World, index: usize) {
+        let frame = match self.frames.get(index) {
+            Some(frame) => frame,
+            None => return,
+        };
+
+        // Remove the entities that are gone by this frame.
+        let gone: Vec<u64> = self.replayed.keys()
+            .filter(|&&id| frame.iter().all(|record| record.id != id))
+            .cloned()
+            .collect();
+        for id in gone {
+            if let Some(entity) = self.replayed.remove(&id) {
+                // Panic in case of entity deletion failure.
+                world.delete_entity(entity)
+                    .expect("The deletion of an entity failed?");
+            }
+        }
+
+        for record in frame {
+            let tint = self.tint(record);
+            match self.replayed.get(&record.id) {
+                Some(&entity) => {
+                    // Panic in case of insertion failure, which can only
+                    // happen if the replay entity is somehow dead.
+                    world.write_storage::<Transform>()
+                        .insert(entity, record.transform.clone())
+                        .expect("The insertion of a transform failed?");
+                    world.write_storage::<Tint>()
+                        .insert(entity, tint)
+                        .expect("The insertion of a tint failed?");
+                },
+                None => {
+                    let entity = world.create_entity()
+                        .with(record.sprite.clone())
+                        .with(record.transform.clone())
+                        .with(tint)
+                        .build();
+                    self.replayed.insert(record.id, entity);
+                },
+            }
+        }
+    }
+}
+
+impl SimpleState for KillCam {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        // Explicit panic if an error is encountered while reading the config
+        // file.
+        self.config = KillCamConfig::from_config_path(
+            self.config_path.join("kill_cam.ron"),
+        ).unwrap();
+
+        {
+            let recording = data.world.read_resource::<KillCamRecording>();
+            self.frames = recording.frames().iter().cloned().collect();
+            self.lethal = recording.lethal;
+        }
+        self.step = data.world.read_resource::<Time>().fixed_seconds();
+
+        // Hide everything the frozen world draws.
+        self.hidden = {
+            let entities = data.world.entities();
+            let sprite_renderers = data.world.read_storage::<SpriteRender>();
+            let hiddens = data.world.read_storage::<Hidden>();
+            (&*entities, &sprite_renderers, !&hiddens).join()
+                .map(|(entity, _, _)| entity)
+                .collect()
+        };
+        {
+            let mut hiddens = data.world.write_storage::<Hidden>();
+            for &entity in self.hidden.iter() {
+                // Panic in case of insertion failure, which can only happen if
+                // the entity is somehow dead.
+                hiddens.insert(entity, Hidden)
+                    .expect("The insertion of a hidden flag failed?");
+            }
+        }
+
+        self.show_frame(data.world, 0);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let replayed: Vec<Entity> = self.replayed.drain()
+            .map(|(_, entity)| entity)
+            .collect();
+        // Panic in case of entity deletion failure.
+        data.world.delete_entities(&replayed)
+            .expect("The deletion of an entity failed?");
+
+        let mut hiddens = data.world.write_storage::<Hidden>();
+        for entity in self.hidden.drain(..) {
+            hiddens.remove(entity);
+        }
+    }
+
+    fn handle_event(
+        &mut self,
+        _: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        match event {
+            // The restart input skips the replay.
+            StateEvent::Input(InputEvent::ActionPressed(ref action))
+                if action == "restart" =>
+            {
+                self.game_over()
+            },
+            _ => Trans::None,
+        }
+    }
+
+    fn update(
+        &mut self,
+        data: &mut StateData<'_, GameData<'_, '_>>,
+    ) -> SimpleTrans {
+        self.elapsed += data.world.read_resource::<Time>().delta_real_seconds();
+
+        let playback_speed = self.config.playback_speed.max(std::f32::EPSILON);
+        let replay_length =
+            self.frames.len() as f32 * self.step / playback_speed;
+        if self.frames.is_empty()
+            || self.elapsed >= replay_length + self.config.hold
+        {
+            return self.game_over();
+        }
+
+        // Hold the final frame once the replay is over.
+        let index = if self.step > 0.0 {
+            (self.elapsed * playback_speed / self.step) as usize
+        } else {
+            0
+        };
+        self.show_frame(data.world, index.min(self.frames.len() - 1));
+        Trans::None
+    }
+}
@@ -9,8 +9,12 @@ use std::path::{Path, PathBuf};
 use amethyst::{
     assets::{AssetStorage, Loader},
     core::transform::Transform,
+    ecs::{Dispatcher, Join},
+    input::{InputHandler, StringBindings},
     prelude::*,
     renderer::{
+        palette::Srgb,
+        resources::Tint,
         Camera,
         ImageFormat,
         SpriteRender,
@@ -19,24 +23,147 @@ use amethyst::{
         Texture,
     },
     utils::ortho_camera::{CameraNormalizeMode, CameraOrtho, CameraOrthoWorldCoordinates},
-    window::{ScreenDimensions},
 };
 
-use crate::resources::{SpriteMap, TimeScale};
-use crate::components::{BoundingBox, Player, RelativeLocomotor};
+use crate::game_over::GameOver;
+use crate::ghost::{self, GhostConfig};
+use crate::kill_cam::KillCam;
+use crate::replay::{InputSource, Replay};
+use crate::rewind::{self, RewindConfig};
+use crate::simulation::{self, SimulationConfig};
+use crate::state_hash::{StateHash, StateHashConfig};
+use crate::resources::{
+    Arena,
+    GameRng,
+    GhostRun,
+    History,
+    KillCamRecording,
+    LaserArchetypes,
+    RunStats,
+    SimulationState,
+    SpriteMap,
+    TickInput,
+    TimeScale,
+};
+use crate::components::{
+    BoundingBox,
+    Dash,
+    Drag,
+    ForceAccumulator,
+    Health,
+    MaxSpeed,
+    Player,
+    PlayerSlots,
+    Polarized,
+    RelativeLocomotor,
+    Rewindable,
+    Shield,
+    TimeDomain,
+};
 use crate::vector::prelude::*;
 
-/// The main gameplay state.
+/// The width of the region of the world visible through the camera. The
+/// world's coordinates do not depend on the window, so that runs play out the
+/// same way regardless of the window's size or DPI.
+const VIEW_WIDTH: StorageTy = 160.0;
+/// The height of the region of the world visible through the camera.
+const VIEW_HEIGHT: StorageTy = 120.0;
+
+/// The main gameplay state. The gameplay simulation is ticked on fixed updates
+/// by this state's own dispatcher, so it only runs while this state is active.
+/// While the world is being rewound, the rewind dispatcher runs instead.
+///
+/// Every tick, the simulation's `TickInput` is taken from the `InputSource`,
+/// which either records the players' input or plays back a replay. The state
+/// at the end of every tick may be hashed, to detect desyncs between a replay
+/// and its recording.
+///
+/// Each run may be raced against a ghost, read from the replay at `ghost_path`.
 pub struct Lase {
     config_path: PathBuf,
+    input_source: InputSource,
+    ghost_path: Option<PathBuf>,
+    state_hash_config: StateHashConfig,
+    dispatcher: Option<Dispatcher<'static, 'static>>,
+    rewind_dispatcher: Option<Dispatcher<'static, 'static>>,
 }
 
 impl Lase {
     pub fn with_config_path(config_path: PathBuf) -> Self {
         Self {
-            config_path
+            config_path,
+            input_source: InputSource::live(),
+            ghost_path: None,
+            state_hash_config: StateHashConfig::default(),
+            dispatcher: None,
+            rewind_dispatcher: None,
         }
     }
+
+    /// Takes the simulation's input from `input_source`.
+    pub fn with_input_source(mut self, input_source: InputSource) -> Self {
+        self.input_source = input_source;
+        self
+    }
+
+    /// Races every run against the ghost recorded in the replay at `path`.
+    pub fn with_ghost_path(mut self, path: PathBuf) -> Self {
+        self.ghost_path = Some(path);
+        self
+    }
+
+    /// Seeds the world for a new run, and builds fresh dispatchers so that no
+    /// system carries any state over from an earlier run. Runs must start from
+    /// the same state for their replays to play out the same way.
+    fn begin_run(&mut self, world: &mut World) {
+        // Explicit panic if an error is encountered while reading the config
+        // files.
+        let ghost_config = GhostConfig::from_config_path(
+            self.config_path.join("ghost.ron"),
+        ).unwrap();
+        // There may not be a ghost to race yet.
+        let ghost = match &self.ghost_path {
+            Some(path) if ghost_config.enabled => Replay::from_path(path).ok(),
+            _ => None,
+        };
+
+        // Face the same lasers as the ghost did.
+        let seed = self.input_source.seed(
+            ghost.as_ref().map(|ghost| ghost.seed),
+        );
+        world.insert(GameRng::from_seed(seed));
+        world.insert(TickInput::default());
+        // Ghosts only make sense on the laser field they were recorded on.
+        let ghost = ghost.filter(|ghost| ghost.seed == seed);
+        ghost::spawn_ghosts(world, ghost_config, ghost.as_ref());
+
+        // Explicit panic if an error is encountered while reading the config
+        // files.
+        let simulation_config = SimulationConfig::from_config_path(
+            self.config_path.join("simulation.ron"),
+        ).unwrap();
+        let rewind_config = RewindConfig::from_config_path(
+            self.config_path.join("rewind.ron"),
+        ).unwrap();
+        self.state_hash_config = StateHashConfig::from_config_path(
+            self.config_path.join("state_hash.ron"),
+        ).unwrap();
+        self.dispatcher = Some(simulation::build_dispatcher(
+            world,
+            &self.config_path,
+            rewind_config,
+        ));
+        self.rewind_dispatcher = Some(rewind::build_dispatcher(
+            world,
+            rewind_config,
+        ));
+
+        self.input_source.begin_run(
+            seed,
+            simulation_config.tick_rate,
+            &self.config_path,
+        );
+    }
 }
 
 impl SimpleState for Lase {
@@ -48,20 +175,125 @@ impl SimpleState for Lase {
         let time_scale = TimeScale::default();
         data.world.insert(time_scale);
 
+        // Explicit panic if an error is encountered while reading the config
+        // file.
+        let laser_archetypes = LaserArchetypes::from_config_path(
+            self.config_path.join("laser_archetypes.ron"),
+        ).unwrap();
+        data.world.insert(laser_archetypes);
+
+        // The arena is centered on the world's origin, where the camera looks.
+        // Explicit panic if an error is encountered while reading the config
+        // file.
+        let arena = Arena::from_config_path(
+            Point2::origin(),
+            self.config_path.join("arena.ron"),
+        ).unwrap();
+        data.world.insert(arena);
+
+        // Set the simulation up before any entities are created, so that
+        // every component storage is registered.
+        self.begin_run(data.world);
+
         // Initialize singleton entities.
-        initialize_player(data.world, self.config_path.join("player.ron"));
+        initialize_players(data.world, &self.config_path);
         initialize_camera(data.world);
     }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        // Closing the game mid-run still saves what was played so far.
+        let survived = data.world.read_resource::<RunStats>().survived;
+        self.input_source.end_run(survived);
+    }
+
+    fn on_pause(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        // Freeze the world while another state is on top of us.
+        *data.world.write_resource::<SimulationState>() =
+            SimulationState::Paused;
+    }
+
+    fn on_resume(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        // We are only ever resumed once the world has been reset for a new
+        // run.
+        self.begin_run(data.world);
+        *data.world.write_resource::<SimulationState>() =
+            SimulationState::Running;
+    }
+
+    fn fixed_update(
+        &mut self,
+        data: StateData<'_, GameData<'_, '_>>,
+    ) -> SimpleTrans {
+        let tick_input = self.input_source.next_tick(
+            &data.world.read_resource::<InputHandler<StringBindings>>(),
+        );
+        *data.world.write_resource::<TickInput>() = tick_input;
+
+        let rewinding = rewind::is_requested(data.world);
+        let dispatcher = if rewinding {
+            self.rewind_dispatcher.as_mut()
+        } else {
+            self.dispatcher.as_mut()
+        };
+        if let Some(dispatcher) = dispatcher {
+            dispatcher.dispatch(&data.world);
+            // Apply this tick's deletions before the next tick, since several
+            // ticks may run within a single frame.
+            data.world.maintain();
+        }
+
+        if self.input_source.wants_hashes(self.state_hash_config.record) {
+            let state = StateHash::compute(
+                data.world,
+                self.state_hash_config.per_entity,
+            );
+            self.input_source.end_tick(state);
+        }
+        // The ghosts wait while the world is rewound, rather than racing ahead
+        // of it. Positions are only recorded on the ticks ghosts step through,
+        // so that a ghost of this run lines up with its ticks.
+        if !rewinding {
+            let positions = ghost::player_positions(data.world);
+            self.input_source.record_positions(positions);
+            data.world.write_resource::<GhostRun>().tick += 1;
+        }
+        Trans::None
+    }
+
+    fn update(
+        &mut self,
+        data: &mut StateData<'_, GameData<'_, '_>>,
+    ) -> SimpleTrans {
+        // The run is over once every player has run out of health.
+        let players_dead = {
+            let players = data.world.read_storage::<Player>();
+            let healths = data.world.read_storage::<Health>();
+            (&players, &healths).join().all(|(_, health)| health.is_dead())
+        };
+
+        if players_dead {
+            let survived = data.world.read_resource::<RunStats>().survived;
+            self.input_source.end_run(survived);
+
+            // Show how the run ended first, if anything was recorded.
+            let config_path = self.config_path.clone();
+            if data.world.read_resource::<KillCamRecording>().is_empty() {
+                Trans::Push(Box::new(GameOver::with_config_path(config_path)))
+            } else {
+                Trans::Push(Box::new(KillCam::with_config_path(config_path)))
+            }
+        } else {
+            Trans::None
+        }
+    }
 }
 
-/// Static function to initialize a player in a world.
-fn initialize_player(world: &mut World, config_path: impl AsRef<Path>) {
-    // TODO: the screen dimensions should be abstracted from the world's
-    // coordinates.
-    let (width, height) = {
-        let dim = world.read_resource::<ScreenDimensions>();
-        (dim.width(), dim.height())
-    };
+/// Static function to initialize the players in a world. The shared player
+/// configuration is read from `player.ron` and the list of players from
+/// `players.ron`, both in the `config_dir` directory.
+pub fn initialize_players(world: &mut World, config_dir: impl AsRef<Path>) {
+    let config_dir = config_dir.as_ref();
+    let arena_center = world.read_resource::<Arena>().center;
 
     // Read the player's sprite sheet.
     let sprite_sheet = {
@@ -71,44 +303,75 @@ fn initialize_player(world: &mut World, config_path: impl AsRef<Path>) {
         sprite_map.get("player").unwrap()
     };
 
-    let sprite_render = SpriteRender {
-        sprite_sheet,
-        sprite_number: 0,
-    };
+    // Explicit panic if an error is encountered while reading the config files.
+    let config = Player::config_from_path(config_dir.join("player.ron"))
+        .unwrap();
+    let slots = PlayerSlots::from_config_path(config_dir.join("players.ron"))
+        .unwrap()
+        .players;
+    // Explicit panic if no player is configured, since the run would be over
+    // before it even started.
+    assert!(!slots.is_empty(), "No player is configured in players.ron");
 
-    // Set the player's position to the middle of the world.
-    let player_origin = Point2::new(width * 0.5, height * 0.5);
-    let local_transform = Transform::from(add_dim(player_origin.coords));
+    // Line the players up side by side in the middle of the arena.
+    // TODO: make the spacing configurable.
+    let spacing = 16.0;
+    let first_offset = -spacing * (slots.len() - 1) as StorageTy * 0.5;
 
-    // Create the player entity with lots of hard coded values.
-    world.create_entity()
-        .with(sprite_render)
-        .with(local_transform)
-        .with(RelativeLocomotor::with_pos(player_origin))
-        // Explicit panic if an error is encountered while reading the config
-        // file.
-        .with(Player::from_config_path(config_path).unwrap())
-        .with(BoundingBox::from(Vec2::new(4.0, 4.0)))
-        .build();
+    for (i, slot) in slots.into_iter().enumerate() {
+        let sprite_render = SpriteRender {
+            sprite_sheet: sprite_sheet.clone(),
+            sprite_number: 0,
+        };
+
+        let player_origin = arena_center
+            + Vec2::new(first_offset + spacing * i as StorageTy, 0.0);
+        let local_transform = Transform::from(add_dim(player_origin.coords));
+
+        let tint = Tint(Srgb::from(slot.color).into());
+        let bounding_box =
+            BoundingBox::from(Vec2::new(slot.hitbox.0, slot.hitbox.1));
+        let health =
+            Health::new(config.max_health, config.invulnerability_time);
+        let dash = Dash::with_cooldown(config.dash_cooldown);
+        let shield = Shield::from_config(config.shield);
+        let polarized = Polarized::from_config(config.polarity);
+        let rewindable = {
+            let mut history = world.write_resource::<History>();
+            Rewindable(history.next_id())
+        };
+
+        let mut builder = world.create_entity()
+            .with(sprite_render)
+            .with(local_transform)
+            .with(tint)
+            .with(RelativeLocomotor::with_pos(player_origin))
+            .with(ForceAccumulator::default())
+            .with(Drag(config.deceleration))
+            .with(MaxSpeed(config.max_speed))
+            .with(Player::new(config.clone(), slot))
+            .with(health)
+            .with(dash)
+            .with(shield)
+            .with(polarized)
+            .with(bounding_box)
+            .with(rewindable);
+        if let Some(name) = &config.time_domain {
+            builder = builder.with(TimeDomain(name.clone()));
+        }
+        builder.build();
+    }
 }
 
 /// Static function to initialize a camera in a world.
 fn initialize_camera(world: &mut World) {
-    // TODO: the screen dimensions should be abstracted from the world's
-    // coordinates.
-    let (width, height) = {
-        let dim = world.read_resource::<ScreenDimensions>();
-        (dim.width(), dim.height())
-    };
-
-    let transform = Transform::from(Vec3::new(width * 0.5, height * 0.5, 10.0));
+    let transform = Transform::from(Vec3::new(0.0, 0.0, 10.0));
 
     // Create the camera entity.
     world
         .create_entity()
         .with(transform)
-        // Upscale rendering by 4x.
-        .with(Camera::standard_2d(width * 0.5 / 4.0, height * 0.5 / 4.0))
+        .with(Camera::standard_2d(VIEW_WIDTH, VIEW_HEIGHT))
         // The CameraOrtho component will ensure that the world coordinates
         // specified by the CameraOrthoWorldCoordinates will remain visible
         // through window resizes. If the aspect ratio of the window does not
@@ -122,12 +385,11 @@ fn initialize_camera(world: &mut World) {
         // letterboxing.
         .with(CameraOrtho::new(
             CameraNormalizeMode::Contain,
-            // Upscale rendering by 4x.
             CameraOrthoWorldCoordinates {
-                left: -width * 0.5 / 4.0,
-                right: width * 0.5 / 4.0,
-                bottom: -height * 0.5 / 4.0,
-                top: height * 0.5 / 4.0,
+                left: -VIEW_WIDTH * 0.5,
+                right: VIEW_WIDTH * 0.5,
+                bottom: -VIEW_HEIGHT * 0.5,
+                top: VIEW_HEIGHT * 0.5,
             }
         ))
         .build();
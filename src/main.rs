@@ -1,5 +1,7 @@
+use std::path::PathBuf;
+
 use amethyst::{
-    core::transform::TransformBundle,
+    core::{transform::TransformBundle, SystemExt},
     input::{InputBundle, StringBindings},
     prelude::*,
     renderer::{
@@ -7,21 +9,34 @@ use amethyst::{
         types::DefaultBackend,
         RenderingBundle,
     },
+    ui::{RenderUi, UiBundle},
     utils::{
         application_root_dir,
         ortho_camera::CameraOrthoSystem,
     },
 };
 
+mod analog;
 mod collisions;
 mod components;
+mod game_over;
+mod ghost;
+mod integration;
+mod kill_cam;
 mod lase;
+mod replay;
 mod resources;
+mod rewind;
+mod simulation;
+mod state_hash;
 mod systems;
 mod util;
 mod vector;
 
 use crate::lase::Lase;
+use crate::replay::{InputSource, Replay, PERSONAL_BEST};
+use crate::resources::SimulationState;
+use crate::simulation::SimulationConfig;
 
 fn main() -> amethyst::Result<()> {
     // For now we log everything.
@@ -29,17 +44,55 @@ fn main() -> amethyst::Result<()> {
 
     let app_root = application_root_dir()?;
 
-    // Configuration files.
-    let config_dir = app_root.join("config");
+    // A run may be played back with `--replay <path>`. Replays are played with
+    // the configuration they were recorded with.
+    // Explicit panic if an error is encountered while reading the replay.
+    let replay = path_from_args("--replay")
+        .map(|path| Replay::from_path(path).unwrap());
+
+    // Configuration files. The gameplay systems read their own configuration
+    // when the simulation dispatcher is built.
+    let config_dir = match &replay {
+        Some(replay) => {
+            // Every instance extracts into its own directory, so that replays
+            // played side by side do not overwrite each other's configs.
+            let dir = std::env::temp_dir()
+                .join(format!("lase_replay_config_{}", std::process::id()));
+            // Explicit panic if the replay's config files cannot be written.
+            replay.extract_configs(&dir).unwrap();
+            dir
+        },
+        None => app_root.join("config"),
+    };
+    let extracted_config_dir = replay.as_ref().map(|_| config_dir.clone());
     let binding_path = config_dir.join("bindings.ron");
     let display_config_path = config_dir.join("display.ron");
-    let laser_collision_config_path = config_dir.join("laser_collision.ron");
-    let laser_spawner_config_path = config_dir.join("laser_spawner.ron");
-    let time_scaling_config_path = config_dir.join("time_scaling.ron");
+    let simulation_config_path = config_dir.join("simulation.ron");
+    let threat_indicator_config_path = config_dir.join("threat_indicator.ron");
+
+    // Explicit panic if an error is encountered while reading the config file.
+    let mut simulation_config =
+        SimulationConfig::from_config_path(simulation_config_path).unwrap();
+
+    // Every run played live is recorded. Runs are raced against the ghost of
+    // the personal best, unless another replay is given with `--ghost <path>`.
+    let replay_dir = app_root.join("replays");
+    let ghost_path = path_from_args("--ghost")
+        .unwrap_or_else(|| replay_dir.join(PERSONAL_BEST));
+    let mut input_source = InputSource::live()
+        .with_recording_dir(replay_dir);
+    if let Some(replay) = replay {
+        simulation_config.tick_rate = replay.tick_rate;
+        input_source = input_source.with_playback(replay);
+    }
 
     let input_bundle = InputBundle::<StringBindings>::new()
         .with_bindings_from_file(binding_path)?;
 
+    // Only the systems that draw the simulation run every frame. The gameplay
+    // systems themselves run on fixed updates; see `simulation`. Drawing
+    // systems are pausable, so that the world freezes while other states are
+    // layered over the main gameplay state.
     let game_data = GameDataBuilder::default()
         .with_bundle(
             RenderingBundle::<DefaultBackend>::new()
@@ -49,50 +102,45 @@ fn main() -> amethyst::Result<()> {
                         .with_clear([0.0, 0.0, 0.0, 1.0]),
                 )
                 .with_plugin(RenderFlat2D::default())
-                .with_plugin(RenderDebugLines::default()),
+                .with_plugin(RenderDebugLines::default())
+                .with_plugin(RenderUi::default()),
         )?
         .with_bundle(TransformBundle::new())?
         .with_bundle(input_bundle)?
+        .with_bundle(UiBundle::<StringBindings>::new())?
         .with(
             CameraOrthoSystem::default(),
             "camera_system",
             &[],
         )
         .with(
-            systems::PlayerSystem,
-            "player_system",
-            &["input_system"]
+            systems::InterpolationSystem.pausable(SimulationState::Running),
+            "interpolation_system",
+            &[]
         )
         .with(
-            // Explicit panic if an error is encountered while reading the
-            // config file.
-            systems::LaserSpawnerSystem::from_config_path(
-                laser_spawner_config_path,
-            ).unwrap(),
-            "laser_system",
-            &["player_system"]
+            systems::GhostSystem::default().pausable(SimulationState::Running),
+            "ghost_system",
+            &[]
         )
         .with(
-            systems::RelativeMotionSystem,
-            "relative_motion_system",
-            &["player_system", "laser_system"]
+            systems::OverlaySystem,
+            "overlay_system",
+            &["interpolation_system"]
         )
         .with(
             // Explicit panic if an error is encountered while reading the
             // config file.
-            systems::LaserCollisionSystem::from_config_path(
-                laser_collision_config_path,
-            ).unwrap(),
-            "laser_collision_system",
-            // We want to check for collisions after everything has moved.
-            &["relative_motion_system"]
+            systems::ThreatIndicatorSystem::from_config_path(
+                threat_indicator_config_path,
+            ).unwrap().pausable(SimulationState::Running),
+            "threat_indicator_system",
+            &["interpolation_system"]
         )
         .with(
-            systems::TimeScalingSystem::from_config_path(
-                time_scaling_config_path,
-            ).unwrap(),
-            "time_scaling_system",
-            &["input_system"]
+            systems::ArenaOutlineSystem,
+            "arena_outline_system",
+            &[]
         )
         .with(
             systems::DebugSystem::default(),
@@ -101,12 +149,32 @@ fn main() -> amethyst::Result<()> {
         );
 
     let assets_dir = app_root.join("assets");
-    let mut game = Application::new(
+    let mut game = Application::build(
         assets_dir,
-        Lase::with_config_path(config_dir),
-        game_data,
-    )?;
+        Lase::with_config_path(config_dir)
+            .with_input_source(input_source)
+            .with_ghost_path(ghost_path),
+    )?
+        .with_fixed_step_length(simulation_config.step_length()?)
+        .build(game_data)?;
     game.run();
 
+    // The extracted config files are only needed while the replay plays.
+    // Failing to remove them is harmless.
+    if let Some(dir) = extracted_config_dir {
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
     Ok(())
 }
+
+/// The path following `flag` on the command line, if any.
+fn path_from_args(flag: &str) -> Option<PathBuf> {
+    let mut args = std::env::args_os().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == flag {
+            return args.next().map(PathBuf::from);
+        }
+    }
+    None
+}
@@ -0,0 +1,291 @@
+//! This module holds the replay format, along with the `InputSource` deciding
+//! whether the simulation is fed the players' live input or recorded input.
+//!
+//! A replay holds everything needed to play a run again: the seed of the
+//! `GameRng`, the contents of every config file, the tick rate and the input
+//! of every simulation tick. Since the simulation runs on a fixed timestep and
+//! only reads input through the `TickInput` resource, feeding it the recorded
+//! input plays the run out exactly as it happened. Replays may also store the
+//! `StateHash` of every tick, in which case the first tick at which the
+//! playback diverges from the recording is reported.
+//!
+//! The run survived the longest so far is also kept as the personal best, which
+//! ghosts follow by default.
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use amethyst::config::{Config, ConfigError};
+use amethyst::input::{InputHandler, StringBindings};
+
+use log::{error, warn};
+
+use serde::{Deserialize, Serialize};
+
+use crate::resources::TickInput;
+use crate::state_hash::StateHash;
+use crate::vector::prelude::*;
+
+/// The file name of the personal best within the replay directory.
+pub const PERSONAL_BEST: &str = "best.ron";
+
+/// A recorded run, stored as RON.
+// TODO: hopefully remove the `Default` derivation pending this issue:
+// https://github.com/amethyst/amethyst/issues/1954
+#[derive(Default, Deserialize, Serialize)]
+pub struct Replay {
+    /// The seed of the run's `GameRng`.
+    pub seed: u64,
+    /// The number of simulation ticks per (real) second.
+    pub tick_rate: f64,
+    /// How long the players survived, in real seconds, leaving out the ticks
+    /// undone by rewinding.
+    #[serde(default)]
+    pub survived: f32,
+    /// The contents of every config file, by file name.
+    pub configs: BTreeMap<String, String>,
+    /// The players' input on every simulation tick.
+    pub inputs: Vec<TickInput>,
+    /// The state at the end of every simulation tick, if it was recorded.
+    #[serde(default)]
+    pub hashes: Vec<StateHash>,
+    /// The position of every player at the end of every simulation tick, for
+    /// ghosts. Ticks spent rewinding are left out. Dead players are `None`.
+    #[serde(default)]
+    pub positions: Vec<Vec<Option<(StorageTy, StorageTy)>>>,
+}
+
+impl Replay {
+    /// Starts the recording of a run played with the config files in
+    /// `config_dir`.
+    pub fn record(
+        seed: u64,
+        tick_rate: f64,
+        config_dir: impl AsRef<Path>,
+    ) -> io::Result<Self> {
+        let mut configs = BTreeMap::new();
+        for entry in fs::read_dir(config_dir)? {
+            let path = entry?.path();
+            if path.extension().map_or(true, |extension| extension != "ron") {
+                continue;
+            }
+            let name = path.file_name().and_then(|name| name.to_str());
+            if let Some(name) = name {
+                configs.insert(name.to_string(), fs::read_to_string(&path)?);
+            }
+        }
+
+        Ok(Self {
+            seed,
+            tick_rate,
+            survived: 0.0,
+            configs,
+            inputs: Vec::new(),
+            hashes: Vec::new(),
+            positions: Vec::new(),
+        })
+    }
+
+    /// Reads a replay from the RON file at `path`.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        // TODO: hopefully change this to just call load pending this issue:
+        // https://github.com/amethyst/amethyst/issues/1954
+        Self::load_no_fallback(path)
+    }
+
+    /// Writes the recorded config files into `dir`, so that the replay can be
+    /// played with the configuration it was recorded with. Anything already in
+    /// `dir` is removed first, so that no stale config file is picked up.
+    pub fn extract_configs(&self, dir: impl AsRef<Path>) -> io::Result<()> {
+        let dir = dir.as_ref();
+        if dir.exists() {
+            fs::remove_dir_all(dir)?;
+        }
+        fs::create_dir_all(dir)?;
+        for (name, contents) in self.configs.iter() {
+            fs::write(dir.join(name), contents)?;
+        }
+        Ok(())
+    }
+}
+
+/// A replay being played back.
+struct Playback {
+    replay: Replay,
+    /// The next tick to be played.
+    tick: usize,
+    /// Whether a divergence has already been reported.
+    diverged: bool,
+}
+
+/// Where the simulation's input comes from. Runs played live are recorded into
+/// the replay directory, if there is one. A replay only covers a single run, so
+/// any run after it is played live.
+pub struct InputSource {
+    /// The directory recorded runs are saved into.
+    directory: Option<PathBuf>,
+    /// The run currently being recorded.
+    recording: Option<Replay>,
+    playback: Option<Playback>,
+}
+
+impl InputSource {
+    /// Plays live, without recording anything.
+    pub fn live() -> Self {
+        Self {
+            directory: None,
+            recording: None,
+            playback: None,
+        }
+    }
+
+    /// Records every run played live into `directory`.
+    pub fn with_recording_dir(mut self, directory: PathBuf) -> Self {
+        self.directory = Some(directory);
+        self
+    }
+
+    /// Plays `replay` back as the first run.
+    pub fn with_playback(mut self, replay: Replay) -> Self {
+        self.playback = Some(Playback {
+            replay,
+            tick: 0,
+            diverged: false,
+        });
+        self
+    }
+
+    /// The seed for the next run. Runs played live use the `preferred` seed
+    /// if there is one, and are seeded randomly otherwise.
+    pub fn seed(&self, preferred: Option<u64>) -> u64 {
+        match &self.playback {
+            Some(playback) => playback.replay.seed,
+            None => preferred.unwrap_or_else(rand::random),
+        }
+    }
+
+    /// Starts a new run, recording it if it is played live.
+    pub fn begin_run(&mut self, seed: u64, tick_rate: f64, config_dir: &Path) {
+        if self.playback.is_some() || self.directory.is_none() {
+            return;
+        }
+        // Explicit panic if an error is encountered while reading the config
+        // files.
+        self.recording =
+            Some(Replay::record(seed, tick_rate, config_dir).unwrap());
+    }
+
+    /// The input for the next simulation tick. Once a replay runs out of
+    /// input, nobody touches anything.
+    pub fn next_tick(
+        &mut self,
+        input: &InputHandler<StringBindings>,
+    ) -> TickInput {
+        if let Some(playback) = &mut self.playback {
+            let tick_input = playback.replay.inputs.get(playback.tick)
+                .cloned()
+                .unwrap_or_default();
+            playback.tick += 1;
+            return tick_input;
+        }
+
+        let tick_input = TickInput::sample(input);
+        if let Some(recording) = &mut self.recording {
+            recording.inputs.push(tick_input.clone());
+        }
+        tick_input
+    }
+
+    /// Whether the state of the current tick should be hashed, either to be
+    /// recorded or to be checked against the replay being played back.
+    pub fn wants_hashes(&self, record: bool) -> bool {
+        match &self.playback {
+            Some(playback) => !playback.replay.hashes.is_empty(),
+            None => record && self.recording.is_some(),
+        }
+    }
+
+    /// Records the state at the end of the tick that was just played, or
+    /// checks it against the replay. Only the first divergence is reported,
+    /// since everything after it is bound to diverge as well.
+    pub fn end_tick(&mut self, state: StateHash) {
+        if let Some(playback) = &mut self.playback {
+            // The tick that was just played is the one before the next one.
+            let tick = playback.tick.saturating_sub(1);
+            let expected = match playback.replay.hashes.get(tick) {
+                Some(expected) => expected,
+                None => return,
+            };
+            if playback.diverged {
+                return;
+            }
+            if let Some(divergence) = state.diverges_from(expected) {
+                playback.diverged = true;
+                warn!(
+                    "the replay diverged from its recording at tick {}: {}",
+                    tick,
+                    divergence,
+                );
+            }
+            return;
+        }
+
+        if let Some(recording) = &mut self.recording {
+            recording.hashes.push(state);
+        }
+    }
+
+    /// Records the players' positions at the end of the tick that was just
+    /// played. This is not called for the ticks spent rewinding.
+    pub fn record_positions(
+        &mut self,
+        positions: Vec<Option<(StorageTy, StorageTy)>>,
+    ) {
+        if let Some(recording) = &mut self.recording {
+            recording.positions.push(positions);
+        }
+    }
+
+    /// Ends the current run, in which the players `survived` for the given
+    /// number of real seconds, and saves its recording. The recording replaces
+    /// the personal best if the players survived for longer. Ending a run that
+    /// has already ended does nothing.
+    pub fn end_run(&mut self, survived: f32) {
+        self.playback = None;
+
+        let recording = self.recording.take();
+        let (mut recording, directory) = match (recording, &self.directory) {
+            (Some(recording), Some(directory)) => (recording, directory),
+            _ => return,
+        };
+        recording.survived = survived;
+
+        // Failing to save a replay should not take the game down with it.
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        let path = directory.join(format!("run_{}.ron", timestamp));
+        if let Err(error) = save(&recording, &path) {
+            error!("failed to save the replay: {}", error);
+        }
+
+        let best_path = directory.join(PERSONAL_BEST);
+        let best_survived = Replay::from_path(&best_path)
+            .map_or(0.0, |best| best.survived);
+        if recording.survived > best_survived {
+            if let Err(error) = save(&recording, &best_path) {
+                error!("failed to save the personal best: {}", error);
+            }
+        }
+    }
+}
+
+/// Saves `replay` at `path`, creating its directory if needed.
+fn save(replay: &Replay, path: &Path) -> Result<(), ConfigError> {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
+    }
+    replay.write(path)
+}
@@ -0,0 +1,118 @@
+//! Holds the shape of the play space. The player is confined to the arena, and
+//! lasers are spawned around it.
+use std::path::Path;
+
+use amethyst::config::{Config, ConfigError};
+
+use serde::{Deserialize, Serialize};
+
+use crate::vector::prelude::*;
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum ArenaShape {
+    Rectangle {
+        half_width: StorageTy,
+        half_height: StorageTy,
+    },
+    Circle {
+        radius: StorageTy,
+    },
+}
+
+impl Default for ArenaShape {
+    fn default() -> Self {
+        ArenaShape::Rectangle {
+            half_width: 0.0,
+            half_height: 0.0,
+        }
+    }
+}
+
+// TODO: hopefully remove the `Default` derivation pending this issue:
+// https://github.com/amethyst/amethyst/issues/1954
+#[derive(Default, Deserialize, Serialize)]
+pub struct ArenaConfig {
+    shape: ArenaShape,
+}
+
+/// The arena itself. The shape is read from a config file, while the center is
+/// determined when the world is initialized.
+#[derive(Clone, Copy, Debug)]
+pub struct Arena {
+    pub center: Point2,
+    pub shape: ArenaShape,
+}
+
+impl Arena {
+    /// Builds an `Arena` centered on `center`, reading its shape from the RON
+    /// file at `path`.
+    pub fn from_config_path(
+        center: Point2,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, ConfigError> {
+        // TODO: hopefully change this to just call load pending this issue:
+        // https://github.com/amethyst/amethyst/issues/1954
+        ArenaConfig::load_no_fallback(path).map(|config| Self {
+            center,
+            shape: config.shape,
+        })
+    }
+
+    /// The radius of the smallest circle around the center that contains the
+    /// whole arena.
+    pub fn bounding_radius(&self) -> StorageTy {
+        match self.shape {
+            ArenaShape::Rectangle { half_width, half_height } => {
+                Vec2::new(half_width, half_height).norm()
+            },
+            ArenaShape::Circle { radius } => radius,
+        }
+    }
+
+    /// Keeps a point with the given position and velocity inside the arena,
+    /// shrunk by `inset` on every side. Points outside are moved back onto the
+    /// edge, and the part of their velocity heading out of the arena is
+    /// removed, so that they slide along the edge.
+    pub fn confine(
+        &self,
+        pos: Point2,
+        velocity: Vec2,
+        inset: StorageTy,
+    ) -> (Point2, Vec2) {
+        let offset = pos - self.center;
+        match self.shape {
+            ArenaShape::Rectangle { half_width, half_height } => {
+                let max_x = (half_width - inset).max(0.0);
+                let max_y = (half_height - inset).max(0.0);
+                let mut offset = offset;
+                let mut velocity = velocity;
+                if offset[0].abs() > max_x {
+                    offset[0] = max_x.copysign(offset[0]);
+                    if velocity[0] * offset[0] > 0.0 {
+                        velocity[0] = 0.0;
+                    }
+                }
+                if offset[1].abs() > max_y {
+                    offset[1] = max_y.copysign(offset[1]);
+                    if velocity[1] * offset[1] > 0.0 {
+                        velocity[1] = 0.0;
+                    }
+                }
+                (self.center + offset, velocity)
+            },
+            ArenaShape::Circle { radius } => {
+                let max_dist = (radius - inset).max(0.0);
+                let dist = offset.norm();
+                if dist <= max_dist || dist == 0.0 {
+                    return (pos, velocity);
+                }
+                let normal = offset / dist;
+                let outward_speed = velocity.dot(&normal).max(0.0);
+                (
+                    self.center + normal * max_dist,
+                    velocity - normal * outward_speed,
+                )
+            },
+        }
+    }
+}
@@ -0,0 +1,20 @@
+/// The meter limiting the use of slow motion. It drains while time is slowed
+/// down, and refills while time runs at full speed. Once it runs dry, the meter
+/// is exhausted and time is forced back to full speed until it has partially
+/// refilled.
+#[derive(Clone, Copy, Debug)]
+pub struct Focus {
+    /// The fraction of the meter that is full.
+    pub fill: f32,
+    pub exhausted: bool,
+}
+
+impl Default for Focus {
+    /// The meter starts out full.
+    fn default() -> Self {
+        Self {
+            fill: 1.0,
+            exhausted: false,
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crate::vector::prelude::*;
+
+/// The recorded run followed by the ghosts, along with how far into it the
+/// live run is.
+pub struct GhostRun {
+    /// The position of every recorded player at the end of every tick. Players
+    /// that were dead are `None`.
+    positions: Vec<Vec<Option<Point2>>>,
+    /// The number of ticks played so far in the live run, leaving out the
+    /// ticks spent rewinding.
+    pub tick: usize,
+    /// Whether the ghosts are drawn.
+    pub visible: bool,
+}
+
+impl GhostRun {
+    pub fn new(positions: Vec<Vec<Option<Point2>>>, visible: bool) -> Self {
+        Self {
+            positions,
+            tick: 0,
+            visible,
+        }
+    }
+
+    /// The number of recorded players.
+    pub fn players(&self) -> usize {
+        self.positions.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    /// The position of the recorded player at `index`, interpolated like the
+    /// live players are. Returns `None` once the recorded player has died, or
+    /// once the recording is over.
+    pub fn position(&self, index: usize, alpha: f32) -> Option<Point2> {
+        let at = |tick: usize| {
+            self.positions.get(tick)
+                .and_then(|players| players.get(index))
+                .and_then(|&pos| pos)
+        };
+
+        let latest = self.tick.checked_sub(1)?;
+        let pos = at(latest)?;
+        let old_pos = latest.checked_sub(1)
+            .and_then(at)
+            .unwrap_or(pos);
+        Some(old_pos + (pos - old_pos) * alpha)
+    }
+}
+
+impl Default for GhostRun {
+    /// Without a recording, there is nothing to draw.
+    fn default() -> Self {
+        Self::new(Vec::new(), true)
+    }
+}
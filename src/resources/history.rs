@@ -0,0 +1,139 @@
+//! Holds the recorded history of the world for the rewind mechanic. A snapshot
+//! of every rewindable entity is recorded each simulation tick, and the oldest
+//! snapshots are dropped once the history is full.
+//!
+//! Snapshots hold every component needed to rebuild an entity from scratch, so
+//! that entities deleted within the history can be brought back. The players'
+//! health and meters are recorded along with the resources that pickups feed,
+//! so that rewinding undoes damage and never hands out a pickup twice.
+use std::collections::VecDeque;
+
+use amethyst::core::Transform;
+use amethyst::renderer::resources::Tint;
+use amethyst::renderer::SpriteRender;
+
+use crate::components::{
+    BoundingBox,
+    Dash,
+    Drag,
+    ForceAccumulator,
+    Health,
+    Laser,
+    Lifetime,
+    MotionModel,
+    Pickup,
+    Polarized,
+    RelativeLocomotor,
+    Shield,
+    TimeDomain,
+};
+use crate::resources::{Focus, SlowTime};
+
+/// The recorded state of a single entity. Components the entity did not have
+/// are `None`.
+#[derive(Clone)]
+pub struct EntitySnapshot {
+    /// The entity's `Rewindable` id.
+    pub id: u64,
+    pub locomotor: RelativeLocomotor,
+    pub transform: Transform,
+    pub laser: Option<Laser>,
+    pub motion_model: Option<MotionModel>,
+    pub lifetime: Option<Lifetime>,
+    /// The `Rewindable` ids of the tether's anchors.
+    pub tether: Option<[u64; 2]>,
+    pub time_domain: Option<TimeDomain>,
+    pub pickup: Option<Pickup>,
+    pub bounding_box: Option<BoundingBox>,
+    pub drag: Option<Drag>,
+    pub accumulator: Option<ForceAccumulator>,
+    pub sprite: Option<SpriteRender>,
+    pub tint: Option<Tint>,
+    pub health: Option<Health>,
+    pub shield: Option<Shield>,
+    pub dash: Option<Dash>,
+    pub polarized: Option<Polarized>,
+}
+
+/// The state of the world at the end of a tick.
+#[derive(Clone)]
+pub struct Snapshot {
+    /// Every rewindable entity.
+    pub entities: Vec<EntitySnapshot>,
+    /// The `RunStats` score, so that the points of a rewound pickup are taken
+    /// back along with it.
+    pub score: u32,
+    pub focus: Focus,
+    pub slow_time: SlowTime,
+}
+
+#[derive(Default)]
+pub struct History {
+    snapshots: VecDeque<Snapshot>,
+    next_id: u64,
+    /// Whether the latest snapshot is the current state of the world, rather
+    /// than a state the world can be rewound to. This is the case until the
+    /// world starts being rewound.
+    latest_is_current: bool,
+}
+
+impl History {
+    /// Hands out a new `Rewindable` id.
+    pub fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    /// Records the latest snapshot, dropping the oldest ones so that no more
+    /// than `capacity` are kept.
+    pub fn push(&mut self, snapshot: Snapshot, capacity: usize) {
+        self.snapshots.push_back(snapshot);
+        self.latest_is_current = true;
+        while self.snapshots.len() > capacity {
+            self.snapshots.pop_front();
+        }
+    }
+
+    /// Removes and returns the latest snapshot.
+    pub fn pop(&mut self) -> Option<Snapshot> {
+        self.latest_is_current = false;
+        self.snapshots.pop_back()
+    }
+
+    /// Forgets the latest snapshot if it is the current state of the world,
+    /// since restoring it would not move anything.
+    pub fn skip_current(&mut self) {
+        if self.latest_is_current {
+            self.pop();
+        }
+    }
+
+    /// Whether there is no earlier state left to rewind to.
+    pub fn is_exhausted(&self) -> bool {
+        self.snapshots.len() <= self.latest_is_current as usize
+    }
+
+    /// Forgets every snapshot. Ids keep counting up, so that they are never
+    /// reused by the entities that are left.
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+        self.latest_is_current = false;
+    }
+}
+
+/// The meter limiting how much the world can be rewound. Rewinding drains it,
+/// and it recharges while time moves forward.
+pub struct RewindMeter {
+    /// The fraction of the meter that is full.
+    pub fill: f32,
+}
+
+impl Default for RewindMeter {
+    /// The meter starts out full.
+    fn default() -> Self {
+        Self {
+            fill: 1.0,
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Holds the short recording of the world replayed by the kill-cam once every
+//! player has died. Only what is needed to draw each entity is recorded, and
+//! the oldest frames are dropped once the recording is full.
+use std::collections::VecDeque;
+
+use amethyst::core::Transform;
+use amethyst::renderer::resources::Tint;
+use amethyst::renderer::SpriteRender;
+
+/// How a single entity was drawn at the end of a tick.
+#[derive(Clone)]
+pub struct RecordedSprite {
+    /// The entity's `Rewindable` id.
+    pub id: u64,
+    pub transform: Transform,
+    pub sprite: SpriteRender,
+    pub tint: Option<Tint>,
+}
+
+/// Every recorded entity at the end of a tick.
+pub type RecordedFrame = Vec<RecordedSprite>;
+
+#[derive(Default)]
+pub struct KillCamRecording {
+    frames: VecDeque<RecordedFrame>,
+    /// The `Rewindable` id of the laser that dealt the latest lethal hit.
+    pub lethal: Option<u64>,
+}
+
+impl KillCamRecording {
+    /// Records the latest frame, dropping the oldest ones so that no more than
+    /// `capacity` are kept.
+    pub fn push(&mut self, frame: RecordedFrame, capacity: usize) {
+        self.frames.push_back(frame);
+        while self.frames.len() > capacity {
+            self.frames.pop_front();
+        }
+    }
+
+    /// Removes and returns the latest frame.
+    pub fn pop(&mut self) -> Option<RecordedFrame> {
+        self.frames.pop_back()
+    }
+
+    /// The recorded frames, from oldest to latest.
+    pub fn frames(&self) -> &VecDeque<RecordedFrame> {
+        &self.frames
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Forgets every frame, along with the lethal laser.
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.lethal = None;
+    }
+}
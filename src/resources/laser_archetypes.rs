@@ -0,0 +1,65 @@
+//! Holds the registry of named laser archetypes. An archetype bundles every
+//! property that determines how a laser looks and behaves, so that spawners can
+//! simply refer to lasers by name. Colors are chosen per archetype so that
+//! players can learn to read a laser's behaviour from its color. The colors of
+//! polarized archetypes should also make their polarity readable at a glance.
+use std::collections::HashMap;
+use std::path::Path;
+
+use amethyst::config::{Config, ConfigError};
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::{MotionKind, Polarity};
+use crate::vector::StorageTy;
+
+/// The length, in world units, of a laser sprite at a scale of `1.0`. Sprites
+/// are drawn along their y-axis.
+pub const LASER_SPRITE_LEN: StorageTy = 32.0;
+/// The width, in world units, of a laser sprite at a scale of `1.0`.
+pub const LASER_SPRITE_WIDTH: StorageTy = 2.0;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LaserArchetype {
+    /// The color of the laser, applied as a tint over the sprite.
+    pub color: (f32, f32, f32),
+    pub len: StorageTy,
+    pub width: StorageTy,
+    /// The initial speed of the laser.
+    pub speed: StorageTy,
+    /// The damage dealt to a player hit by the laser.
+    pub damage: f32,
+    #[serde(default)]
+    pub motion_model: MotionKind,
+    /// Lasers are neutral unless stated otherwise.
+    #[serde(default)]
+    pub polarity: Polarity,
+    /// The name of the time channel the laser moves in, if not the world's.
+    #[serde(default)]
+    pub time_domain: Option<String>,
+    /// The id of the laser's sprite sheet in the `SpriteMap`.
+    pub sprite: String,
+}
+
+// TODO: hopefully remove the `Default` derivation pending this issue:
+// https://github.com/amethyst/amethyst/issues/1954
+#[derive(Default, Deserialize, Serialize)]
+pub struct LaserArchetypes {
+    archetypes: HashMap<String, LaserArchetype>,
+}
+
+impl LaserArchetypes {
+    /// Builds the registry by reading the RON file at `path`.
+    pub fn from_config_path(
+        path: impl AsRef<Path>
+    ) -> Result<Self, ConfigError> {
+        // TODO: hopefully change this to just call load pending this issue:
+        // https://github.com/amethyst/amethyst/issues/1954
+        Self::load_no_fallback(path)
+    }
+
+    /// Gets the archetype registered under `name`.
+    pub fn get(&self, name: &str) -> Option<&LaserArchetype> {
+        self.archetypes.get(name)
+    }
+}
@@ -1,5 +1,46 @@
+mod arena;
+pub use arena::{Arena, ArenaShape};
+
+mod focus;
+pub use focus::Focus;
+
+mod game_rng;
+pub use game_rng::GameRng;
+
+mod ghost_run;
+pub use ghost_run::GhostRun;
+
+mod history;
+pub use history::{EntitySnapshot, History, RewindMeter, Snapshot};
+
+mod kill_cam;
+pub use kill_cam::{KillCamRecording, RecordedFrame, RecordedSprite};
+
+mod laser_archetypes;
+pub use laser_archetypes::{
+    LaserArchetype,
+    LaserArchetypes,
+    LASER_SPRITE_LEN,
+    LASER_SPRITE_WIDTH,
+};
+
+mod run_stats;
+pub use run_stats::RunStats;
+
+mod simulation_state;
+pub use simulation_state::SimulationState;
+
+mod slow_time;
+pub use slow_time::SlowTime;
+
 mod sprite_map;
 pub use sprite_map::SpriteMap;
 
+mod tick_input;
+pub use tick_input::TickInput;
+
+mod time_channels;
+pub use time_channels::{TimeChannels, WORLD_CHANNEL};
+
 mod time_scale;
 pub use time_scale::TimeScale;
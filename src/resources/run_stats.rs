@@ -0,0 +1,17 @@
+/// Summary statistics about the current run, shown when the run ends.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RunStats {
+    /// The real time, in seconds, that the run has lasted.
+    pub real_time: f32,
+    /// The scaled time, in seconds, that the run has lasted.
+    pub scaled_time: f32,
+    /// The real time, in seconds, that the players have survived. Unlike
+    /// `real_time`, the ticks undone by rewinding are left out.
+    pub survived: f32,
+    pub lasers_spawned: u32,
+    pub hits_taken: u32,
+    /// The lasers absorbed by players of the same polarity.
+    pub lasers_absorbed: u32,
+    /// The points collected from pickups.
+    pub score: u32,
+}
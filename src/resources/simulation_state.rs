@@ -0,0 +1,16 @@
+/// Whether the gameplay systems should currently run. Gameplay systems are
+/// registered as pausable on this resource, so that other states can be
+/// layered on top of the main gameplay state without the world moving
+/// underneath them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SimulationState {
+    Running,
+    Paused,
+}
+
+impl Default for SimulationState {
+    /// The simulation runs unless told otherwise.
+    fn default() -> Self {
+        SimulationState::Running
+    }
+}
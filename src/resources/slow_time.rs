@@ -0,0 +1,14 @@
+/// A temporary cap on the time scale, granted by pickups. While `remaining` is
+/// positive, the time scale may not exceed `max_time_scale`. The remaining
+/// duration is measured in real (unscaled) seconds.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SlowTime {
+    pub remaining: f32,
+    pub max_time_scale: f32,
+}
+
+impl SlowTime {
+    pub fn is_active(self) -> bool {
+        self.remaining > 0.0
+    }
+}
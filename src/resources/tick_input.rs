@@ -0,0 +1,49 @@
+//! The players' input for the current simulation tick. Gameplay systems read
+//! their input from here rather than from the `InputHandler`, so that recorded
+//! input can be played back in its place. The accessors mirror those of the
+//! `InputHandler`.
+use std::collections::{BTreeMap, BTreeSet};
+
+use amethyst::input::{InputHandler, StringBindings};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct TickInput {
+    /// The value of every axis that is not at rest.
+    axes: BTreeMap<String, f32>,
+    /// Every action that is held down.
+    actions: BTreeSet<String>,
+}
+
+impl TickInput {
+    /// Captures the current state of every bound axis and action.
+    pub fn sample(input: &InputHandler<StringBindings>) -> Self {
+        let axes = input.bindings.axes()
+            .filter_map(|axis| {
+                input.axis_value(axis)
+                    .filter(|&value| value != 0.0)
+                    .map(|value| (axis.clone(), value))
+            })
+            .collect();
+        let actions = input.bindings.actions()
+            .filter(|action| input.action_is_down(action).unwrap_or(false))
+            .cloned()
+            .collect();
+
+        Self {
+            axes,
+            actions,
+        }
+    }
+
+    /// The value of `axis`, or `None` if it is at rest.
+    pub fn axis_value(&self, axis: &str) -> Option<f32> {
+        self.axes.get(axis).cloned()
+    }
+
+    /// Whether `action` is held down.
+    pub fn action_is_down(&self, action: &str) -> Option<bool> {
+        Some(self.actions.contains(action))
+    }
+}
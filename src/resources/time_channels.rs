@@ -0,0 +1,34 @@
+//! Holds the current time scale of every named time channel. Entities choose a
+//! channel with a `TimeDomain`, and the `TimeScalingSystem` drives every
+//! channel from its configuration once per tick.
+use std::collections::HashMap;
+
+use crate::components::TimeDomain;
+
+/// The name of the channel followed by entities without a `TimeDomain`. It
+/// always matches the `TimeScale`.
+pub const WORLD_CHANNEL: &str = "world";
+
+#[derive(Default)]
+pub struct TimeChannels {
+    scales: HashMap<String, f32>,
+}
+
+impl TimeChannels {
+    pub fn set(&mut self, name: &str, scale: f32) {
+        self.scales.insert(name.to_owned(), scale);
+    }
+
+    pub fn get(&self, name: &str) -> Option<f32> {
+        self.scales.get(name).cloned()
+    }
+
+    /// The time scale applied to an entity in `domain`. Entities outside of
+    /// any domain, or in a channel that is not configured, follow the world's
+    /// time scale.
+    pub fn scale(&self, domain: Option<&TimeDomain>) -> f32 {
+        domain.and_then(|domain| self.get(&domain.0))
+            .or_else(|| self.get(WORLD_CHANNEL))
+            .unwrap_or(1.0)
+    }
+}
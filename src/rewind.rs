@@ -0,0 +1,81 @@
+//! This module holds the rewind mechanic's configuration, and builds the
+//! dispatcher that runs in place of the simulation while the world is being
+//! rewound. The `HistorySystem` records the world as part of the simulation,
+//! and the `RewindSystem` plays that history backwards.
+//!
+//! The world is rewound while any living player holds their rewind input, as
+//! long as there is history left and the `RewindMeter` is not empty.
+use std::path::Path;
+
+use amethyst::config::{Config, ConfigError};
+use amethyst::core::ArcThreadPool;
+use amethyst::ecs::{Dispatcher, DispatcherBuilder, Join};
+use amethyst::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::{Health, Player};
+use crate::resources::{History, RewindMeter, TickInput};
+use crate::systems;
+
+// TODO: hopefully remove the `Default` derivation pending this issue:
+// https://github.com/amethyst/amethyst/issues/1954
+#[derive(Clone, Copy, Default, Deserialize, Serialize)]
+pub struct RewindConfig {
+    /// How far back the world can be rewound, in real seconds.
+    pub history_seconds: f32,
+    /// How long a full meter lasts while rewinding, in real seconds.
+    pub max_duration: f32,
+    /// How long an empty meter takes to refill, in real seconds.
+    pub recharge_time: f32,
+    /// The number of recorded ticks undone per tick of rewinding.
+    pub speed: u32,
+}
+
+impl RewindConfig {
+    /// Reads the rewind configuration from the RON file at `path`.
+    pub fn from_config_path(
+        path: impl AsRef<Path>
+    ) -> Result<Self, ConfigError> {
+        // TODO: hopefully change this to just call load pending this issue:
+        // https://github.com/amethyst/amethyst/issues/1954
+        Self::load_no_fallback(path)
+    }
+}
+
+/// Builds the rewind dispatcher, and sets its systems up in `world`.
+pub fn build_dispatcher(
+    world: &mut World,
+    config: RewindConfig,
+) -> Dispatcher<'static, 'static> {
+    let pool = world.read_resource::<ArcThreadPool>().clone();
+    let mut dispatcher = DispatcherBuilder::new()
+        .with_pool(pool)
+        .with(
+            systems::RewindSystem::from_config(config),
+            "rewind_system",
+            &[]
+        )
+        .build();
+
+    dispatcher.setup(world);
+    dispatcher
+}
+
+/// Determines if the world should be rewound this tick.
+pub fn is_requested(world: &World) -> bool {
+    if world.read_resource::<RewindMeter>().fill <= 0.0
+        || world.read_resource::<History>().is_exhausted()
+    {
+        return false;
+    }
+
+    let input = world.read_resource::<TickInput>();
+    let players = world.read_storage::<Player>();
+    let healths = world.read_storage::<Health>();
+    (&players, (&healths).maybe()).join()
+        .filter(|(_, health)| health.map_or(true, |health| !health.is_dead()))
+        .any(|(player, _)| {
+            input.action_is_down(&player.binding("rewind")).unwrap_or(false)
+        })
+}
@@ -0,0 +1,223 @@
+//! This module builds the dispatcher for the gameplay simulation. Gameplay
+//! systems run on amethyst's fixed update at a configurable tick rate, so that
+//! the physics behave the same regardless of the frame rate. Systems that only
+//! draw the simulation are left on the regular, per-frame dispatcher.
+//!
+//! Systems on the simulation dispatcher step by `Time::fixed_seconds()`, and
+//! the simulation only ticks while the main gameplay state is active.
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use amethyst::config::{Config, ConfigError};
+use amethyst::core::ArcThreadPool;
+use amethyst::ecs::{Dispatcher, DispatcherBuilder};
+use amethyst::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::rewind::RewindConfig;
+use crate::systems;
+
+// TODO: hopefully remove the `Default` derivation pending this issue:
+// https://github.com/amethyst/amethyst/issues/1954
+#[derive(Default, Deserialize, Serialize)]
+pub struct SimulationConfig {
+    /// The number of simulation ticks per (real) second.
+    pub tick_rate: f64,
+}
+
+impl SimulationConfig {
+    /// Reads the simulation configuration from the RON file at `path`.
+    pub fn from_config_path(
+        path: impl AsRef<Path>
+    ) -> Result<Self, ConfigError> {
+        // TODO: hopefully change this to just call load pending this issue:
+        // https://github.com/amethyst/amethyst/issues/1954
+        Self::load_no_fallback(path)
+    }
+
+    /// The length of a single simulation tick. Fails unless the tick rate is a
+    /// positive, finite number.
+    pub fn step_length(&self) -> Result<Duration, ConfigError> {
+        if !(self.tick_rate > 0.0 && self.tick_rate.is_finite()) {
+            return Err(ConfigError::File(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "the tick rate must be positive, but it is {}",
+                    self.tick_rate,
+                ),
+            )));
+        }
+        Ok(Duration::from_secs_f64(1.0 / self.tick_rate))
+    }
+}
+
+/// Builds the simulation dispatcher, reading the systems' configuration from
+/// the `config_dir` directory, and sets its systems up in `world`.
+pub fn build_dispatcher(
+    world: &mut World,
+    config_dir: impl AsRef<Path>,
+    rewind_config: RewindConfig,
+) -> Dispatcher<'static, 'static> {
+    let config_dir = config_dir.as_ref();
+    let cleanup_config_path = config_dir.join("cleanup.ron");
+    let fence_spawner_config_path = config_dir.join("fence_spawner.ron");
+    let kill_cam_config_path = config_dir.join("kill_cam.ron");
+    let laser_spawner_config_path = config_dir.join("laser_spawner.ron");
+    let pickup_spawner_config_path = config_dir.join("pickups.ron");
+    let relative_motion_config_path = config_dir.join("relative_motion.ron");
+    let time_scaling_config_path = config_dir.join("time_scaling.ron");
+
+    let pool = world.read_resource::<ArcThreadPool>().clone();
+    let mut dispatcher = DispatcherBuilder::new()
+        .with_pool(pool)
+        .with(
+            systems::PlayerSystem,
+            "player_system",
+            &[]
+        )
+        .with(
+            // Explicit panic if an error is encountered while reading the
+            // config file.
+            systems::LaserSpawnerSystem::from_config_path(
+                laser_spawner_config_path,
+            ).unwrap(),
+            "laser_system",
+            &["player_system"]
+        )
+        .with(
+            // Explicit panic if an error is encountered while reading the
+            // config file.
+            systems::FenceSpawnerSystem::from_config_path(
+                fence_spawner_config_path,
+            ).unwrap(),
+            "fence_spawner_system",
+            &["player_system"]
+        )
+        .with(
+            // Explicit panic if an error is encountered while reading the
+            // config file.
+            systems::PickupSpawnerSystem::from_config_path(
+                pickup_spawner_config_path,
+            ).unwrap(),
+            "pickup_spawner_system",
+            &[]
+        )
+        .with(
+            // Explicit panic if an error is encountered while reading the
+            // config file.
+            systems::TimeScalingSystem::from_config_path(
+                time_scaling_config_path,
+            ).unwrap(),
+            "time_scaling_system",
+            // Time follows this tick's input, and every time channel is set
+            // before anything moves.
+            &["player_system"]
+        )
+        .with(
+            systems::PhysicsSystem,
+            "physics_system",
+            // Forces are integrated once everything has had the chance to push
+            // this tick's entities around.
+            &[
+                "player_system",
+                "laser_system",
+                "fence_spawner_system",
+                "time_scaling_system",
+            ]
+        )
+        .with(
+            // Explicit panic if an error is encountered while reading the
+            // config file.
+            systems::RelativeMotionSystem::from_config_path(
+                relative_motion_config_path,
+            ).unwrap(),
+            "relative_motion_system",
+            &["physics_system", "pickup_spawner_system"]
+        )
+        .with(
+            systems::TetherSystem,
+            "tether_system",
+            // Fences are stretched between their anchors once they have moved.
+            &["relative_motion_system"]
+        )
+        .with(
+            systems::ShieldSystem,
+            "shield_system",
+            // Shields deflect lasers after they have moved, but before they
+            // get the chance to hit anybody.
+            &["tether_system"]
+        )
+        .with(
+            systems::PolaritySystem,
+            "polarity_system",
+            &[]
+        )
+        .with(
+            systems::LaserCollisionSystem,
+            "laser_collision_system",
+            // We want to check for collisions after everything has moved, and
+            // against this tick's polarities.
+            &["tether_system", "shield_system", "polarity_system"]
+        )
+        .with(
+            systems::PickupCollectionSystem,
+            "pickup_collection_system",
+            // Pickups are collected once the players have moved, and bombs
+            // clear lasers after they have had their chance to hit.
+            &["relative_motion_system", "laser_collision_system"]
+        )
+        .with(
+            systems::InvulnerabilitySystem::default(),
+            "invulnerability_system",
+            // Invulnerability granted by a hit this tick starts counting down
+            // next tick.
+            &["laser_collision_system"]
+        )
+        .with(
+            // Explicit panic if an error is encountered while reading the
+            // config file.
+            systems::CleanupSystem::from_config_path(
+                cleanup_config_path,
+            ).unwrap(),
+            "cleanup_system",
+            // Expired entities are removed once everything has had a chance to
+            // interact with them this tick.
+            &["laser_collision_system", "pickup_collection_system"]
+        )
+        .with(
+            systems::SlowTimeSystem,
+            "slow_time_system",
+            // Slow time pickups start counting down on the tick they are
+            // collected, and cap the time scale from the next tick on.
+            &["pickup_collection_system"]
+        )
+        .with(
+            systems::RunStatsSystem,
+            "run_stats_system",
+            // Scaled time is counted at this tick's time scale.
+            &["time_scaling_system"]
+        )
+        .with_barrier()
+        // The world is recorded once everything else is done with this tick.
+        .with(
+            systems::HistorySystem::from_config(rewind_config),
+            "history_system",
+            &[]
+        )
+        .with(
+            // Explicit panic if an error is encountered while reading the
+            // config file.
+            systems::KillCamRecorderSystem::from_config_path(
+                kill_cam_config_path,
+            ).unwrap(),
+            "kill_cam_recorder_system",
+            // Entities are identified by the ids handed out by the history.
+            &["history_system"]
+        )
+        .build();
+
+    dispatcher.setup(world);
+    dispatcher
+}
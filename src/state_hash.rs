@@ -0,0 +1,243 @@
+//! This module hashes the state of the simulation at the end of every tick,
+//! in order to detect desyncs. The positions and velocities of every player and
+//! laser, the `TimeScale` and the state of the `GameRng` are all hashed into a
+//! single 64-bit checksum.
+//!
+//! Replays may store the checksum of every tick. While a replay is played back,
+//! the checksums are compared, and the first tick that diverges is reported.
+//! Every entity is hashed separately as well unless disabled, so that the
+//! report can name the entities that diverged. Entities are named by their
+//! `Rewindable` ids, which are handed out in the order they are spawned.
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use amethyst::config::{Config, ConfigError};
+use amethyst::ecs::Join;
+use amethyst::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::{Laser, Player, RelativeLocomotor, Rewindable};
+use crate::resources::{GameRng, TimeScale};
+
+// TODO: hopefully remove the `Default` derivation pending this issue:
+// https://github.com/amethyst/amethyst/issues/1954
+#[derive(Clone, Copy, Default, Deserialize, Serialize)]
+pub struct StateHashConfig {
+    /// Whether recorded runs store the checksum of every tick.
+    pub record: bool,
+    /// Whether every entity is hashed separately as well. This makes replays
+    /// considerably larger.
+    pub per_entity: bool,
+}
+
+impl StateHashConfig {
+    /// Reads the state hashing configuration from the RON file at `path`.
+    pub fn from_config_path(
+        path: impl AsRef<Path>
+    ) -> Result<Self, ConfigError> {
+        // TODO: hopefully change this to just call load pending this issue:
+        // https://github.com/amethyst/amethyst/issues/1954
+        Self::load_no_fallback(path)
+    }
+}
+
+/// The checksums of the simulation's state at the end of a tick.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct StateHash {
+    /// The checksum of the whole state.
+    pub world: u64,
+    /// The checksum of every hashed entity, by `Rewindable` id. This is empty
+    /// unless entities are hashed separately.
+    #[serde(default)]
+    pub entities: BTreeMap<u64, u64>,
+}
+
+impl StateHash {
+    /// Hashes the current state of `world`.
+    pub fn compute(world: &World, per_entity: bool) -> Self {
+        let rewindables = world.read_storage::<Rewindable>();
+        let locomotors = world.read_storage::<RelativeLocomotor>();
+        let players = world.read_storage::<Player>();
+        let lasers = world.read_storage::<Laser>();
+
+        // Hash the entities in the order of their ids, rather than in the order
+        // they are stored in.
+        let entities: BTreeMap<u64, u64> = (
+            &rewindables,
+            &locomotors,
+            (&players).maybe(),
+            (&lasers).maybe(),
+        ).join()
+            .filter(|(_, _, player, laser)| player.is_some() || laser.is_some())
+            .map(|(rewindable, locomotor, _, _)| {
+                let mut hasher = Fnv1a::default();
+                hasher.write_u64(rewindable.0);
+                hasher.write_f32(locomotor.pos[0]);
+                hasher.write_f32(locomotor.pos[1]);
+                hasher.write_f32(locomotor.velocity[0]);
+                hasher.write_f32(locomotor.velocity[1]);
+                (rewindable.0, hasher.finish())
+            })
+            .collect();
+
+        let mut hasher = Fnv1a::default();
+        hasher.write_f32(world.read_resource::<TimeScale>().0);
+        hasher.write_u64(world.read_resource::<GameRng>().state());
+        for (&id, &hash) in entities.iter() {
+            hasher.write_u64(id);
+            hasher.write_u64(hash);
+        }
+
+        Self {
+            world: hasher.finish(),
+            entities: if per_entity { entities } else { BTreeMap::new() },
+        }
+    }
+
+    /// Compares this state with the `expected` state, describing the
+    /// differences if there are any.
+    pub fn diverges_from(&self, expected: &StateHash) -> Option<Divergence> {
+        if self.world == expected.world {
+            return None;
+        }
+
+        // Entities can only be told apart if both states hashed them.
+        let mut entities = Vec::new();
+        if !self.entities.is_empty() && !expected.entities.is_empty() {
+            for (id, hash) in expected.entities.iter() {
+                if self.entities.get(id) != Some(hash) {
+                    entities.push(*id);
+                }
+            }
+            for id in self.entities.keys() {
+                if !expected.entities.contains_key(id) {
+                    entities.push(*id);
+                }
+            }
+            entities.sort();
+        }
+
+        Some(Divergence {
+            entities,
+        })
+    }
+}
+
+/// How a state differs from the state it was expected to be in.
+#[derive(Debug)]
+pub struct Divergence {
+    /// The `Rewindable` ids of the entities that differ, if they are known.
+    /// Entities that only exist in one of the states are included.
+    pub entities: Vec<u64>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.entities.is_empty() {
+            write!(f, "the state differs, but no entity is known to differ")
+        } else {
+            write!(f, "the entities with ids {:?} differ", self.entities)
+        }
+    }
+}
+
+/// The 64-bit FNV-1a hash. Unlike the standard library's default hasher, its
+/// output is guaranteed not to change between versions of Rust. Values are
+/// written in little-endian order, so that it does not change between
+/// platforms either.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_f32(&mut self, value: f32) {
+        self.write(&value.to_bits().to_le_bytes());
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A state with the given entity checksums. The world checksum only has to
+    /// tell states apart, so it is derived from the entities' checksums.
+    fn state(entities: &[(u64, u64)]) -> StateHash {
+        let mut hasher = Fnv1a::default();
+        for &(id, hash) in entities {
+            hasher.write_u64(id);
+            hasher.write_u64(hash);
+        }
+        StateHash {
+            world: hasher.finish(),
+            entities: entities.iter().cloned().collect(),
+        }
+    }
+
+    #[test]
+    fn fnv1a_matches_reference() {
+        let mut hasher = Fnv1a::default();
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn equal_states_do_not_diverge() {
+        let expected = state(&[(0, 10), (3, 30)]);
+        assert!(state(&[(0, 10), (3, 30)]).diverges_from(&expected).is_none());
+    }
+
+    #[test]
+    fn divergence_names_changed_entity() {
+        let expected = state(&[(0, 10), (3, 30), (7, 70)]);
+        let divergence = state(&[(0, 10), (3, 31), (7, 70)])
+            .diverges_from(&expected)
+            .expect("The states should diverge");
+        assert_eq!(divergence.entities, vec![3]);
+        assert_eq!(
+            divergence.to_string(),
+            "the entities with ids [3] differ",
+        );
+    }
+
+    #[test]
+    fn divergence_names_missing_and_extra_entities() {
+        let expected = state(&[(0, 10), (3, 30)]);
+        let divergence = state(&[(0, 10), (5, 50)])
+            .diverges_from(&expected)
+            .expect("The states should diverge");
+        assert_eq!(divergence.entities, vec![3, 5]);
+    }
+
+    #[test]
+    fn divergence_without_entity_hashes_names_nothing() {
+        let expected = StateHash {
+            world: 1,
+            entities: BTreeMap::new(),
+        };
+        let divergence = state(&[(0, 10)])
+            .diverges_from(&expected)
+            .expect("The states should diverge");
+        assert!(divergence.entities.is_empty());
+    }
+}
@@ -0,0 +1,53 @@
+//! This module draws the outline of the arena with the debug lines renderer,
+//! so that players can see where the edges are.
+use amethyst::ecs::{ReadExpect, System, Write};
+use amethyst::renderer::debug_drawing::DebugLines;
+use amethyst::renderer::palette::Srgba;
+
+use crate::resources::{Arena, ArenaShape};
+use crate::vector::prelude::*;
+
+/// The number of line segments used to approximate a circular arena.
+const CIRCLE_SEGMENTS: u32 = 64;
+
+pub struct ArenaOutlineSystem;
+
+impl<'s> System<'s> for ArenaOutlineSystem {
+    type SystemData = (
+        ReadExpect<'s, Arena>,
+        Write<'s, DebugLines>,
+    );
+
+    fn run(&mut self, (arena, mut debug_lines): Self::SystemData) {
+        let color = Srgba::new(0.5, 0.5, 0.5, 1.0);
+        let center = arena.center;
+
+        match arena.shape {
+            ArenaShape::Rectangle { half_width, half_height } => {
+                let corners = [
+                    Vec2::new(-half_width, -half_height),
+                    Vec2::new(half_width, -half_height),
+                    Vec2::new(half_width, half_height),
+                    Vec2::new(-half_width, half_height),
+                ];
+                for (i, corner) in corners.iter().enumerate() {
+                    let a = center + *corner;
+                    let b = center + corners[(i + 1) % corners.len()];
+                    debug_lines.draw_line(
+                        Point3::new(a[0], a[1], 0.0),
+                        Point3::new(b[0], b[1], 0.0),
+                        color,
+                    );
+                }
+            },
+            ArenaShape::Circle { radius } => {
+                debug_lines.draw_circle(
+                    Point3::new(center[0], center[1], 0.0),
+                    radius,
+                    CIRCLE_SEGMENTS,
+                    color,
+                );
+            },
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! This module is responsible for removing entities that are no longer
+//! relevant to the game. Entities are removed either when their `Lifetime`
+//! expires (counted down in the time of their `TimeDomain`), or, in the case
+//! of lasers, when they leave the configured world bounds.
+use std::path::Path;
+
+use amethyst::config::{Config, ConfigError};
+use amethyst::core::{Time, Transform};
+use amethyst::ecs::{
+    Entities,
+    Join,
+    Read,
+    ReadExpect,
+    ReadStorage,
+    System,
+    WriteStorage,
+};
+use amethyst::utils::ortho_camera::CameraOrtho;
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::{Laser, Lifetime, RelativeLocomotor, TimeDomain};
+use crate::resources::{Arena, TimeChannels};
+use crate::vector::prelude::*;
+
+/// The region outside of which lasers are removed.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum DespawnBounds {
+    /// Lasers are never removed for being out of bounds.
+    Unbounded,
+    /// The region of the world visible through the camera, grown by `margin`
+    /// in every direction. The margin should be large enough to contain the
+    /// spawn points of lasers, or they will be removed as soon as they appear.
+    VisibleRegion { margin: StorageTy },
+    /// The bounding circle of the arena, grown by `margin`. As with the visible
+    /// region, the margin should contain the spawn points of lasers.
+    Arena { margin: StorageTy },
+    /// A fixed rectangle in world coordinates.
+    Fixed {
+        left: StorageTy,
+        right: StorageTy,
+        bottom: StorageTy,
+        top: StorageTy,
+    },
+}
+
+impl Default for DespawnBounds {
+    fn default() -> Self {
+        DespawnBounds::Unbounded
+    }
+}
+
+// TODO: hopefully remove the `Default` derivation pending this issue:
+// https://github.com/amethyst/amethyst/issues/1954
+#[derive(Default, Deserialize, Serialize)]
+pub struct CleanupConfig {
+    bounds: DespawnBounds,
+}
+
+pub struct CleanupSystem {
+    config: CleanupConfig,
+}
+
+impl CleanupSystem {
+    /// Builds a `CleanupSystem` with the provided `CleanupConfig`.
+    pub fn from_config(config: impl Into<CleanupConfig>) -> Self {
+        Self {
+            config: config.into(),
+        }
+    }
+
+    /// Builds a `CleanupSystem` by reading the RON file at `path`.
+    pub fn from_config_path(
+        path: impl AsRef<Path>
+    ) -> Result<Self, ConfigError> {
+        // TODO: hopefully change this to just call load pending this issue:
+        // https://github.com/amethyst/amethyst/issues/1954
+        CleanupConfig::load_no_fallback(path).map(Self::from_config)
+    }
+}
+
+impl<'s> System<'s> for CleanupSystem {
+    // TODO: is this idiomatic? Can I package these in some more convenient
+    // structure?
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        WriteStorage<'s, Lifetime>,
+        ReadStorage<'s, Laser>,
+        ReadStorage<'s, TimeDomain>,
+        ReadStorage<'s, RelativeLocomotor>,
+        ReadStorage<'s, CameraOrtho>,
+        ReadStorage<'s, Transform>,
+        ReadExpect<'s, Arena>,
+        Read<'s, TimeChannels>,
+        Read<'s, Time>,
+        Entities<'s>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            mut lifetimes,
+            lasers,
+            time_domains,
+            locomotors,
+            cameras,
+            transforms,
+            arena,
+            channels,
+            time,
+            entities,
+        ): Self::SystemData
+    ) {
+        // Count down every lifetime, removing the entities that have expired.
+        let lifetime_iter = (&entities, &mut lifetimes, (&time_domains).maybe())
+            .join();
+        for (entity, lifetime, time_domain) in lifetime_iter {
+            let scale = channels.scale(time_domain);
+            lifetime.remaining -= scale * time.fixed_seconds();
+            if lifetime.is_expired() {
+                // Panic in case of entity deletion failure.
+                entities.delete(entity)
+                    .expect("The deletion of an entity failed?");
+            }
+        }
+
+        // Compute the bounds as `(min, max)` corners, if there are any.
+        let bounds = match self.config.bounds {
+            DespawnBounds::Unbounded => None,
+            DespawnBounds::VisibleRegion { margin } => {
+                (&cameras, &transforms).join()
+                    .next()
+                    .map(|(camera, transform)| {
+                        let center = transform.translation();
+                        let coords = &camera.world_coordinates;
+                        (
+                            Point2::new(
+                                center[0] + coords.left - margin,
+                                center[1] + coords.bottom - margin,
+                            ),
+                            Point2::new(
+                                center[0] + coords.right + margin,
+                                center[1] + coords.top + margin,
+                            ),
+                        )
+                    })
+            },
+            DespawnBounds::Arena { margin } => {
+                let extent =
+                    Vec2::from_element(arena.bounding_radius() + margin);
+                Some((arena.center - extent, arena.center + extent))
+            },
+            DespawnBounds::Fixed { left, right, bottom, top } => Some((
+                Point2::new(left, bottom),
+                Point2::new(right, top),
+            )),
+        };
+
+        if let Some((min, max)) = bounds {
+            let laser_iter = (&entities, &lasers, &locomotors).join();
+            for (entity, _, locomotor) in laser_iter {
+                let pos = locomotor.pos;
+                let in_bounds = pos[0] >= min[0] && pos[0] <= max[0]
+                    && pos[1] >= min[1] && pos[1] <= max[1];
+                if !in_bounds {
+                    // Panic in case of entity deletion failure.
+                    entities.delete(entity)
+                        .expect("The deletion of an entity failed?");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,390 @@
+//! This module is responsible for spawning laser fences. A fence is a laser
+//! tethered between two anchor entities, each of which moves on its own. The
+//! `TetherSystem` keeps the laser stretched between its anchors.
+//!
+//! Each spawn picks one of the configured `FencePattern`s at random and places
+//! it around the arena, approaching from a random direction.
+use std::path::Path;
+
+use amethyst::core::{Time, Transform};
+use amethyst::config::{Config, ConfigError};
+use amethyst::ecs::{
+    Entities,
+    Read,
+    ReadExpect,
+    System,
+    Write,
+    WriteStorage,
+};
+use amethyst::renderer::palette::Srgb;
+use amethyst::renderer::resources::Tint;
+use amethyst::renderer::SpriteRender;
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::{
+    Laser,
+    Lifetime,
+    MotionKind,
+    MotionModel,
+    RelativeLocomotor,
+    Rewindable,
+    Tether,
+    TimeDomain,
+};
+use crate::resources::{
+    Arena,
+    GameRng,
+    History,
+    LaserArchetypes,
+    SpriteMap,
+    TimeScale,
+};
+use crate::vector::prelude::*;
+
+/// The arrangements of anchors that can be spawned. In the descriptions below,
+/// "ahead" means the direction from the arena's center towards the spawn point.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum FencePattern {
+    /// A straight bar, perpendicular to its direction of travel, sweeping
+    /// across the arena's center.
+    Sweep {
+        half_length: StorageTy,
+        speed: StorageTy,
+    },
+    /// A bar pointing at the arena's center that rotates around it, optionally
+    /// closing in on it.
+    RotatingBar {
+        /// The distance from the center to the near end of the bar.
+        inner_radius: StorageTy,
+        length: StorageTy,
+        /// In radians per second.
+        angular_velocity: StorageTy,
+        radial_speed: StorageTy,
+    },
+    /// Two blades sharing a hinge outside of the arena. The blades start open
+    /// on either side of the arena's center and rotate towards each other.
+    Scissors {
+        blade_length: StorageTy,
+        /// The initial angle between each blade and the line from the hinge to
+        /// the arena's center, in radians.
+        opening_angle: StorageTy,
+        /// In radians per second.
+        angular_velocity: StorageTy,
+    },
+}
+
+// TODO: hopefully remove the `Default` derivation pending this issue:
+// https://github.com/amethyst/amethyst/issues/1954
+#[derive(Default, Deserialize, Serialize)]
+pub struct FenceSpawnerConfig {
+    /// The time between fence spawns, in scaled seconds.
+    spawn_interval: StorageTy,
+    /// The distance outside of the arena at which fences are spawned.
+    spawn_dist: StorageTy,
+    /// How long each fence lives, in scaled seconds.
+    lifetime: StorageTy,
+    /// The name of the archetype of the fence lasers. The archetype's length,
+    /// speed and motion model are ignored, since fences are shaped by their
+    /// anchors.
+    archetype: String,
+    /// The patterns from which each spawn picks one at random. If this is
+    /// empty, no fences are spawned.
+    patterns: Vec<FencePattern>,
+}
+
+/// The description of a single anchor to be spawned.
+struct AnchorSpec {
+    pos: Point2,
+    velocity: Vec2,
+    /// An optional motion model, along with the center it orbits.
+    motion: Option<(MotionKind, Point2)>,
+}
+
+impl AnchorSpec {
+    fn fixed(pos: Point2) -> Self {
+        Self::moving(pos, Vec2::zeros())
+    }
+
+    fn moving(pos: Point2, velocity: Vec2) -> Self {
+        Self {
+            pos,
+            velocity,
+            motion: None,
+        }
+    }
+
+    fn orbiting(pos: Point2, center: Point2, kind: MotionKind) -> Self {
+        Self {
+            pos,
+            velocity: Vec2::zeros(),
+            motion: Some((kind, center)),
+        }
+    }
+}
+
+pub struct FenceSpawnerSystem {
+    /// The scaled time since the last spawn.
+    timer: StorageTy,
+    config: FenceSpawnerConfig,
+}
+
+impl FenceSpawnerSystem {
+    /// Builds a `FenceSpawnerSystem` with the provided `FenceSpawnerConfig`.
+    pub fn from_config(config: impl Into<FenceSpawnerConfig>) -> Self {
+        Self {
+            timer: 0.0,
+            config: config.into(),
+        }
+    }
+
+    /// Builds a `FenceSpawnerSystem` by reading the RON file at `path`.
+    pub fn from_config_path(
+        path: impl AsRef<Path>
+    ) -> Result<Self, ConfigError> {
+        // TODO: hopefully change this to just call load pending this issue:
+        // https://github.com/amethyst/amethyst/issues/1954
+        FenceSpawnerConfig::load_no_fallback(path).map(Self::from_config)
+    }
+
+    /// Lays out the anchors of `pattern` and the pairs of anchor indices to
+    /// tether together. `ahead` is the unit vector from the arena's center
+    /// towards the spawn point.
+    fn layout(
+        &self,
+        pattern: &FencePattern,
+        arena: &Arena,
+        ahead: Vec2,
+    ) -> (Vec<AnchorSpec>, Vec<(usize, usize)>) {
+        // The spawn distance is measured from the edge of the arena, like that
+        // of the `LaserSpawnerSystem`.
+        let center = arena.center;
+        let spawn_dist = arena.bounding_radius() + self.config.spawn_dist;
+        let spawn_point = center + ahead * spawn_dist;
+        let side = Vec2::new(ahead[1], -ahead[0]);
+
+        match *pattern {
+            FencePattern::Sweep { half_length, speed } => {
+                let velocity = -ahead * speed;
+                let offset = side * half_length;
+                (
+                    vec![
+                        AnchorSpec::moving(spawn_point + offset, velocity),
+                        AnchorSpec::moving(spawn_point - offset, velocity),
+                    ],
+                    vec![(0, 1)],
+                )
+            },
+            FencePattern::RotatingBar {
+                inner_radius,
+                length,
+                angular_velocity,
+                radial_speed,
+            } => {
+                let kind = MotionKind::Orbit { angular_velocity, radial_speed };
+                (
+                    vec![
+                        AnchorSpec::orbiting(
+                            center + ahead * inner_radius,
+                            center,
+                            kind.clone(),
+                        ),
+                        AnchorSpec::orbiting(
+                            center + ahead * (inner_radius + length),
+                            center,
+                            kind,
+                        ),
+                    ],
+                    vec![(0, 1)],
+                )
+            },
+            FencePattern::Scissors {
+                blade_length,
+                opening_angle,
+                angular_velocity,
+            } => {
+                // The blades start on either side of the line from the hinge
+                // to the arena's center, and rotate back towards it.
+                let towards_center = -ahead * blade_length;
+                let left = Rot2::new(opening_angle) * towards_center;
+                let right = Rot2::new(-opening_angle) * towards_center;
+                (
+                    vec![
+                        AnchorSpec::fixed(spawn_point),
+                        AnchorSpec::orbiting(
+                            spawn_point + left,
+                            spawn_point,
+                            MotionKind::Orbit {
+                                angular_velocity: -angular_velocity,
+                                radial_speed: 0.0,
+                            },
+                        ),
+                        AnchorSpec::orbiting(
+                            spawn_point + right,
+                            spawn_point,
+                            MotionKind::Orbit {
+                                angular_velocity,
+                                radial_speed: 0.0,
+                            },
+                        ),
+                    ],
+                    vec![(0, 1), (0, 2)],
+                )
+            },
+        }
+    }
+}
+
+impl<'s> System<'s> for FenceSpawnerSystem {
+    // TODO: is this idiomatic? Can I package these in some more convenient
+    // structure?
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        WriteStorage<'s, Laser>,
+        WriteStorage<'s, Tether>,
+        WriteStorage<'s, Lifetime>,
+        WriteStorage<'s, MotionModel>,
+        WriteStorage<'s, TimeDomain>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, RelativeLocomotor>,
+        WriteStorage<'s, SpriteRender>,
+        WriteStorage<'s, Tint>,
+        WriteStorage<'s, Rewindable>,
+        ReadExpect<'s, Arena>,
+        Read<'s, SpriteMap>,
+        Read<'s, LaserArchetypes>,
+        Read<'s, TimeScale>,
+        Read<'s, Time>,
+        Write<'s, GameRng>,
+        Write<'s, History>,
+        Entities<'s>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            mut lasers,
+            mut tethers,
+            mut lifetimes,
+            mut motion_models,
+            mut time_domains,
+            mut transforms,
+            mut locomotors,
+            mut sprite_renderers,
+            mut tints,
+            mut rewindables,
+            arena,
+            sprite_map,
+            archetypes,
+            time_scale,
+            time,
+            mut rng,
+            mut history,
+            entities,
+        ): Self::SystemData
+    ) {
+        if self.config.patterns.is_empty() {
+            return;
+        }
+
+        self.timer += time_scale.0 * time.fixed_seconds();
+        if self.timer < self.config.spawn_interval {
+            return;
+        }
+        self.timer = 0.0;
+
+        // Pick a random pattern and a random direction to spawn it in.
+        let patterns = &self.config.patterns;
+        let pattern = &patterns[rng.index(patterns.len())];
+        let rand_theta = rng.unit() * PI * 2.0;
+        let ahead = Rot2::new(rand_theta) * Vec2::y();
+
+        let (anchor_specs, spans) = self.layout(pattern, &arena, ahead);
+
+        // Explicit panic if the fence refers to an unknown archetype, since
+        // this is a mistake in the config files.
+        let name = &self.config.archetype;
+        let archetype = archetypes.get(name)
+            .unwrap_or_else(|| panic!("Unknown laser archetype {:?}", name));
+        let color = archetype.color;
+        let sprite_sheet = sprite_map.get(&archetype.sprite).unwrap();
+
+        // Build the anchors. They are drawn as small squares by squashing the
+        // laser sprite.
+        let anchors: Vec<_> = anchor_specs.into_iter()
+            .map(|spec| {
+                let locomotor =
+                    RelativeLocomotor::with_velocity(spec.pos, spec.velocity);
+                let motion_model = spec.motion.map(|(kind, center)| {
+                    MotionModel::new(kind, &locomotor, center)
+                });
+
+                let mut transform = Transform::from(add_dim(spec.pos.coords));
+                transform.set_scale(Vec3::new(2.0, 0.125, 1.0));
+
+                let anchor = entities.build_entity()
+                    .with(SpriteRender {
+                        sprite_sheet: sprite_sheet.clone(),
+                        sprite_number: 0,
+                    }, &mut sprite_renderers)
+                    .with(transform, &mut transforms)
+                    .with(locomotor, &mut locomotors)
+                    .with(Lifetime::new(self.config.lifetime), &mut lifetimes)
+                    .with(Tint(Srgb::from(color).into()), &mut tints)
+                    .with(Rewindable(history.next_id()), &mut rewindables)
+                    .build();
+
+                if let Some(motion_model) = motion_model {
+                    // Panic in case of insertion failure, which can only happen
+                    // if the entity we just built is somehow dead.
+                    motion_models.insert(anchor, motion_model)
+                        .expect("The insertion of a motion model failed?");
+                }
+
+                if let Some(name) = &archetype.time_domain {
+                    // Panic in case of insertion failure, which can only happen
+                    // if the entity we just built is somehow dead.
+                    time_domains.insert(anchor, TimeDomain(name.clone()))
+                        .expect("The insertion of a time domain failed?");
+                }
+
+                anchor
+            })
+            .collect();
+
+        // Build the lasers stretched between the anchors. Their position,
+        // rotation and length are filled in by the `TetherSystem`.
+        for (a, b) in spans {
+            let pos = locomotors.get(anchors[a]).unwrap().pos;
+            let fence = entities.build_entity()
+                .with(SpriteRender {
+                    sprite_sheet: sprite_sheet.clone(),
+                    sprite_number: 0,
+                }, &mut sprite_renderers)
+                .with(Transform::from(add_dim(pos.coords)), &mut transforms)
+                .with(RelativeLocomotor::with_pos(pos), &mut locomotors)
+                .with(Laser {
+                    archetype: name.clone(),
+                    color,
+                    len: 0.0,
+                    width: archetype.width,
+                    damage: archetype.damage,
+                    polarity: archetype.polarity,
+                }, &mut lasers)
+                .with(Tether::between(anchors[a], anchors[b]), &mut tethers)
+                .with(Lifetime::new(self.config.lifetime), &mut lifetimes)
+                .with(Tint(Srgb::from(color).into()), &mut tints)
+                .with(Rewindable(history.next_id()), &mut rewindables)
+                .build();
+
+            // The fence shares its anchors' time channel, so that they expire
+            // together.
+            if let Some(name) = &archetype.time_domain {
+                // Panic in case of insertion failure, which can only happen if
+                // the entity we just built is somehow dead.
+                time_domains.insert(fence, TimeDomain(name.clone()))
+                    .expect("The insertion of a time domain failed?");
+            }
+        }
+    }
+}
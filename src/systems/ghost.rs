@@ -0,0 +1,77 @@
+//! This module moves the ghosts along their recorded run. Like the
+//! `InterpolationSystem`, it runs every frame and places each ghost between its
+//! positions at the previous and the latest tick. Ghosts are hidden once their
+//! recorded player has died, and can be toggled with the `toggle_ghost` input.
+use amethyst::core::{Hidden, Time, Transform};
+use amethyst::ecs::{
+    Entities,
+    Join,
+    Read,
+    ReadStorage,
+    System,
+    Write,
+    WriteStorage,
+};
+use amethyst::input::{InputHandler, StringBindings};
+
+use crate::components::Ghost;
+use crate::resources::GhostRun;
+use crate::vector::prelude::*;
+
+#[derive(Default)]
+pub struct GhostSystem {
+    /// Whether the toggle input was held last frame.
+    was_held: bool,
+}
+
+impl<'s> System<'s> for GhostSystem {
+    // TODO: is this idiomatic? Can I package these in some more convenient
+    // structure?
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadStorage<'s, Ghost>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, Hidden>,
+        Write<'s, GhostRun>,
+        Read<'s, InputHandler<StringBindings>>,
+        Read<'s, Time>,
+        Entities<'s>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            ghosts,
+            mut transforms,
+            mut hiddens,
+            mut ghost_run,
+            input,
+            time,
+            entities,
+        ): Self::SystemData
+    ) {
+        // The ghosts are toggled when the input is first pressed.
+        let held = input.action_is_down("toggle_ghost").unwrap_or(false);
+        if held && !self.was_held {
+            ghost_run.visible = !ghost_run.visible;
+        }
+        self.was_held = held;
+
+        let alpha = time.interpolation_alpha();
+        let ghost_iter = (&entities, &ghosts, &mut transforms).join();
+        for (entity, ghost, transform) in ghost_iter {
+            match ghost_run.position(ghost.index, alpha) {
+                Some(pos) if ghost_run.visible => {
+                    transform.set_translation(add_dim(pos.coords));
+                    hiddens.remove(entity);
+                },
+                _ => {
+                    // Panic in case of insertion failure, which can only
+                    // happen if the ghost is somehow dead.
+                    hiddens.insert(entity, Hidden)
+                        .expect("The insertion of a Hidden component failed?");
+                },
+            }
+        }
+    }
+}
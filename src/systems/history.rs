@@ -0,0 +1,176 @@
+//! This module records the world into the `History` at the end of every
+//! simulation tick, for the rewind mechanic. Every entity with a `Rewindable`
+//! id and a `RelativeLocomotor` is recorded. Ids are handed out by whatever
+//! spawns the entity, in the order it spawns them, so that they do not depend
+//! on the reuse of entity indices. The `RewindMeter` recharges while the world
+//! is recorded.
+//!
+//! The players' health, shield, dash and polarity are recorded along with the
+//! score and the time scaling meters, since pickups and lasers change them.
+use amethyst::core::{Time, Transform};
+use amethyst::ecs::{
+    Entities,
+    Join,
+    Read,
+    ReadStorage,
+    System,
+    Write,
+};
+use amethyst::renderer::resources::Tint;
+use amethyst::renderer::SpriteRender;
+
+use crate::components::{
+    BoundingBox,
+    Dash,
+    Drag,
+    ForceAccumulator,
+    Health,
+    Laser,
+    Lifetime,
+    MotionModel,
+    Pickup,
+    Polarized,
+    RelativeLocomotor,
+    Rewindable,
+    Shield,
+    Tether,
+    TimeDomain,
+};
+use crate::resources::{
+    EntitySnapshot,
+    Focus,
+    History,
+    RewindMeter,
+    RunStats,
+    SlowTime,
+    Snapshot,
+};
+use crate::rewind::RewindConfig;
+
+pub struct HistorySystem {
+    config: RewindConfig,
+}
+
+impl HistorySystem {
+    /// Builds a `HistorySystem` with the provided `RewindConfig`.
+    pub fn from_config(config: impl Into<RewindConfig>) -> Self {
+        Self {
+            config: config.into(),
+        }
+    }
+}
+
+impl<'s> System<'s> for HistorySystem {
+    // TODO: is this idiomatic? Can I package these in some more convenient
+    // structure?
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadStorage<'s, Rewindable>,
+        ReadStorage<'s, RelativeLocomotor>,
+        ReadStorage<'s, Transform>,
+        ReadStorage<'s, Laser>,
+        ReadStorage<'s, MotionModel>,
+        ReadStorage<'s, Lifetime>,
+        ReadStorage<'s, Tether>,
+        ReadStorage<'s, TimeDomain>,
+        ReadStorage<'s, Pickup>,
+        ReadStorage<'s, BoundingBox>,
+        ReadStorage<'s, Drag>,
+        ReadStorage<'s, ForceAccumulator>,
+        ReadStorage<'s, SpriteRender>,
+        ReadStorage<'s, Tint>,
+        ReadStorage<'s, Health>,
+        ReadStorage<'s, Shield>,
+        ReadStorage<'s, Dash>,
+        ReadStorage<'s, Polarized>,
+        Write<'s, History>,
+        Write<'s, RewindMeter>,
+        Read<'s, RunStats>,
+        Read<'s, Focus>,
+        Read<'s, SlowTime>,
+        Read<'s, Time>,
+        Entities<'s>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            rewindables,
+            locomotors,
+            transforms,
+            lasers,
+            motion_models,
+            lifetimes,
+            tethers,
+            time_domains,
+            pickups,
+            bounding_boxes,
+            drags,
+            accumulators,
+            sprite_renderers,
+            tints,
+            healths,
+            shields,
+            dashes,
+            polarizeds,
+            mut history,
+            mut meter,
+            stats,
+            focus,
+            slow_time,
+            time,
+            entities,
+        ): Self::SystemData
+    ) {
+        let delta = time.fixed_seconds();
+        if self.config.recharge_time > 0.0 {
+            meter.fill =
+                (meter.fill + delta / self.config.recharge_time).min(1.0);
+        }
+
+        let entity_iter =
+            (&entities, &rewindables, &locomotors, &transforms).join();
+        let entity_snapshots = entity_iter
+            .map(|(entity, rewindable, locomotor, transform)| EntitySnapshot {
+                id: rewindable.0,
+                locomotor: locomotor.clone(),
+                transform: transform.clone(),
+                laser: lasers.get(entity).cloned(),
+                motion_model: motion_models.get(entity).cloned(),
+                lifetime: lifetimes.get(entity).cloned(),
+                // Anchors are spawned with ids like everything else.
+                tether: tethers.get(entity).and_then(|tether| {
+                    let [a, b] = tether.anchors;
+                    match (rewindables.get(a), rewindables.get(b)) {
+                        (Some(a), Some(b)) => Some([a.0, b.0]),
+                        _ => None,
+                    }
+                }),
+                time_domain: time_domains.get(entity).cloned(),
+                pickup: pickups.get(entity).cloned(),
+                bounding_box: bounding_boxes.get(entity).cloned(),
+                drag: drags.get(entity).cloned(),
+                accumulator: accumulators.get(entity).cloned(),
+                sprite: sprite_renderers.get(entity).cloned(),
+                tint: tints.get(entity).cloned(),
+                health: healths.get(entity).cloned(),
+                shield: shields.get(entity).cloned(),
+                dash: dashes.get(entity).cloned(),
+                polarized: polarizeds.get(entity).cloned(),
+            })
+            .collect();
+        let snapshot = Snapshot {
+            entities: entity_snapshots,
+            score: stats.score,
+            focus: *focus,
+            slow_time: *slow_time,
+        };
+
+        let capacity = if delta > 0.0 {
+            (self.config.history_seconds / delta).ceil() as usize
+        } else {
+            0
+        };
+        history.push(snapshot, capacity);
+    }
+}
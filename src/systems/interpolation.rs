@@ -0,0 +1,31 @@
+//! The simulation ticks at a fixed rate, which generally does not match the
+//! frame rate. To keep motion smooth, this system places every moving entity's
+//! `Transform` between its position at the previous tick and its position at
+//! the latest tick, according to how far the current frame is between ticks.
+//!
+//! This runs every frame, on the regular dispatcher. As a consequence, entities
+//! are drawn up to one tick behind the simulation.
+use amethyst::core::{Time, Transform};
+use amethyst::ecs::{Join, Read, ReadStorage, System, WriteStorage};
+
+use crate::components::RelativeLocomotor;
+use crate::vector::prelude::*;
+
+pub struct InterpolationSystem;
+
+impl<'s> System<'s> for InterpolationSystem {
+    type SystemData = (
+        ReadStorage<'s, RelativeLocomotor>,
+        WriteStorage<'s, Transform>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (locomotors, mut transforms, time): Self::SystemData) {
+        let alpha = time.interpolation_alpha();
+        for (locomotor, transform) in (&locomotors, &mut transforms).join() {
+            let pos = locomotor.old_pos
+                + (locomotor.pos - locomotor.old_pos) * alpha;
+            transform.set_translation(add_dim(pos.coords));
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! This module counts down the invulnerability of entities with `Health`, in
+//! real (unscaled) time. Players blink while they are invulnerable, which is
+//! done by toggling their `Hidden` component. Dead players are hidden until the
+//! game is restarted.
+use amethyst::core::{Hidden, Time};
+use amethyst::ecs::{Entities, Join, Read, ReadStorage, System, WriteStorage};
+
+use crate::components::{Health, Player};
+
+#[derive(Default)]
+pub struct InvulnerabilitySystem {
+    /// The real time, in seconds, that has passed since the system started.
+    /// This drives the blinking.
+    clock: f32,
+}
+
+impl<'s> System<'s> for InvulnerabilitySystem {
+    // TODO: is this idiomatic? Can I package these in some more convenient
+    // structure?
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadStorage<'s, Player>,
+        WriteStorage<'s, Health>,
+        WriteStorage<'s, Hidden>,
+        Read<'s, Time>,
+        Entities<'s>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            players,
+            mut healths,
+            mut hiddens,
+            time,
+            entities,
+        ): Self::SystemData
+    ) {
+        let delta = time.fixed_seconds();
+        self.clock += delta;
+
+        for health in (&mut healths).join() {
+            health.invulnerable_for =
+                (health.invulnerable_for - delta).max(0.0);
+        }
+
+        for (entity, player, health) in (&entities, &players, &healths).join() {
+            // Spend the first half of each blink period hidden. Dead players
+            // stay hidden.
+            let blink_phase =
+                (self.clock * player.config.blink_frequency).fract();
+            let hidden = health.is_dead()
+                || (health.is_invulnerable() && blink_phase < 0.5);
+            if hidden {
+                // Panic in case of insertion failure, which can only happen if
+                // the player is somehow dead.
+                hiddens.insert(entity, Hidden)
+                    .expect("The insertion of a `Hidden` component failed?");
+            } else {
+                hiddens.remove(entity);
+            }
+        }
+    }
+}
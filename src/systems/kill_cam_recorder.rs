@@ -0,0 +1,96 @@
+//! This module records the last few seconds of the world into the
+//! `KillCamRecording` at the end of every simulation tick. Every drawn entity
+//! with a `Rewindable` id is recorded at its latest simulated position.
+use std::path::Path;
+
+use amethyst::config::ConfigError;
+use amethyst::core::{Time, Transform};
+use amethyst::ecs::{Join, Read, ReadStorage, System, Write};
+use amethyst::renderer::resources::Tint;
+use amethyst::renderer::SpriteRender;
+
+use crate::components::{RelativeLocomotor, Rewindable};
+use crate::kill_cam::KillCamConfig;
+use crate::resources::{KillCamRecording, RecordedSprite};
+
+pub struct KillCamRecorderSystem {
+    config: KillCamConfig,
+}
+
+impl KillCamRecorderSystem {
+    /// Builds a `KillCamRecorderSystem` with the provided `KillCamConfig`.
+    pub fn from_config(config: impl Into<KillCamConfig>) -> Self {
+        Self {
+            config: config.into(),
+        }
+    }
+
+    /// Builds a `KillCamRecorderSystem` by reading the RON file at `path`.
+    pub fn from_config_path(
+        path: impl AsRef<Path>
+    ) -> Result<Self, ConfigError> {
+        KillCamConfig::from_config_path(path).map(Self::from_config)
+    }
+}
+
+impl<'s> System<'s> for KillCamRecorderSystem {
+    // TODO: is this idiomatic? Can I package these in some more convenient
+    // structure?
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadStorage<'s, Rewindable>,
+        ReadStorage<'s, RelativeLocomotor>,
+        ReadStorage<'s, Transform>,
+        ReadStorage<'s, SpriteRender>,
+        ReadStorage<'s, Tint>,
+        Write<'s, KillCamRecording>,
+        Read<'s, Time>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            rewindables,
+            locomotors,
+            transforms,
+            sprite_renderers,
+            tints,
+            mut recording,
+            time,
+        ): Self::SystemData
+    ) {
+        let delta = time.fixed_seconds();
+        let capacity = if delta > 0.0 {
+            (self.config.duration / delta).ceil() as usize
+        } else {
+            0
+        };
+        if capacity == 0 {
+            return;
+        }
+
+        let frame = (
+            &rewindables,
+            &locomotors,
+            &transforms,
+            &sprite_renderers,
+            (&tints).maybe(),
+        ).join()
+            .map(|(rewindable, locomotor, transform, sprite, tint)| {
+                // Transforms lag behind the simulation by up to a tick, since
+                // they are interpolated every frame.
+                let mut transform = transform.clone();
+                transform.set_translation_x(locomotor.pos[0]);
+                transform.set_translation_y(locomotor.pos[1]);
+                RecordedSprite {
+                    id: rewindable.0,
+                    transform,
+                    sprite: sprite.clone(),
+                    tint: tint.cloned(),
+                }
+            })
+            .collect();
+
+        recording.push(frame, capacity);
+    }
+}
@@ -1,56 +1,47 @@
 //! This module is responsible for checking for the collision of lasers. It may
 //! be generalized for collisions between any object in some future iteration
 //! of this code.
-use std::path::Path;
-
-use amethyst::config::{Config, ConfigError};
-use amethyst::ecs::{Entities, Join, ReadStorage, System,};
-
-use serde::{Deserialize, Serialize};
+//!
+//! Lasers hitting a player of the same polarity are absorbed, charging the
+//! player's meter instead of damaging them. A laser dealing a lethal hit is
+//! remembered for the kill-cam.
+use amethyst::ecs::{Entities, Join, ReadStorage, System, Write, WriteStorage};
 
 use crate::collisions::box_collision::*;
-use crate::components::{BoundingBox, Laser, Player, RelativeLocomotor};
-use crate::vector::StorageTy;
-
-// TODO: hopefully remove the `Default` derivation pending this issue:
-// https://github.com/amethyst/amethyst/issues/1954
-#[derive(Default, Deserialize, Serialize)]
-pub struct LaserCollisionConfig {
-    despawn_dist: StorageTy,
-}
-
-pub struct LaserCollisionSystem {
-    despawn_dist_squared: StorageTy,
-}
-
-impl LaserCollisionSystem {
-    /// Builds a `LaserCollisionSystem` with the provided `LaserCollisionConfig`.
-    pub fn from_config(config: impl Into<LaserCollisionConfig>) -> Self {
-        let config = config.into();
-        let despawn_dist_squared = config.despawn_dist * config.despawn_dist;
-        Self {
-            despawn_dist_squared,
-        }
-    }
+use crate::components::{
+    BoundingBox,
+    Dash,
+    Health,
+    Laser,
+    Player,
+    Polarized,
+    RelativeLocomotor,
+    Rewindable,
+    Tether,
+};
+use crate::resources::{KillCamRecording, RunStats};
+use crate::vector::prelude::*;
 
-    /// Builds a `LaserCollisionSystem` by reading the RON file at `path`.
-    pub fn from_config_path(
-        path: impl AsRef<Path>
-    ) -> Result<Self, ConfigError> {
-        // TODO: hopefully change this to just call load pending this issue:
-        // https://github.com/amethyst/amethyst/issues/1954
-        LaserCollisionConfig::load_no_fallback(path).map(Self::from_config)
-    }
-}
+/// Lasers that drift away are not this system's concern; they are removed by
+/// the `CleanupSystem`.
+pub struct LaserCollisionSystem;
 
 impl<'s> System<'s> for LaserCollisionSystem {
     // TODO: is this idiomatic? Can I package these in some more convenient
     // structure?
+    #[allow(clippy::type_complexity)]
     type SystemData = (
         ReadStorage<'s, Laser>,
         ReadStorage<'s, Player>,
         ReadStorage<'s, BoundingBox>,
         ReadStorage<'s, RelativeLocomotor>,
+        ReadStorage<'s, Tether>,
+        ReadStorage<'s, Rewindable>,
+        ReadStorage<'s, Dash>,
+        WriteStorage<'s, Health>,
+        WriteStorage<'s, Polarized>,
+        Write<'s, RunStats>,
+        Write<'s, KillCamRecording>,
         Entities<'s>,
     );
 
@@ -63,55 +54,124 @@ impl<'s> System<'s> for LaserCollisionSystem {
             players,
             bounding_boxes,
             locomotors,
+            tethers,
+            rewindables,
+            dashes,
+            mut healths,
+            mut polarizeds,
+            mut stats,
+            mut kill_cam,
             entities
         ): Self::SystemData
     ) {
-        // Get the player's bounding box and locomotor.
+        // Get the position, bounding box, health and polarity of every living
+        // player, along with whether they are dashing through lasers.
         // TODO: is this idiomatic?
-        let (bb, player_locomotor) = (&players, &bounding_boxes, &locomotors)
-            .join()
-            .next()
-            .map(|(_, &bb, l)| (bb, l))
-            // This unwrap shouldn't fail since we should always have a player
-            // with these components. If there is some way to get the singleton
-            // player more conveniently, this will go away.
-            .unwrap();
-
-        let player_pos = player_locomotor.pos;
-        let player_radius = bb.dist();
-
-        // Calculate the player's actual bounding box as a `Rect`.
-        let player_rect = Rect::from_bounding_box(player_pos, bb);
+        let mut targets: Vec<_> = (
+            &players,
+            &bounding_boxes,
+            &locomotors,
+            &mut healths,
+            (&mut polarizeds).maybe(),
+            (&dashes).maybe(),
+        ).join()
+            .filter(|(_, _, _, health, _, _)| !health.is_dead())
+            .map(|(_, &bb, locomotor, health, polarized, dash)| {
+                let dodging = dash.map_or(false, |dash| dash.is_invulnerable());
+                (locomotor.pos, bb, health, polarized, dodging)
+            })
+            .collect();
 
         // The iterator over all laser entities. We include `entities` in our
         // join because we need a reference to the actual entity to remove it
         // if a collision has occurred.
-        let laser_iter = (&entities, &lasers, &locomotors).join();
-        for (entity, laser, locomotor) in laser_iter {
-            let laser_pos = locomotor.pos;
-            let dist_vec = player_pos.coords - laser_pos.coords;
-            let dist_squared = dist_vec.norm_squared();
-            let player_radius_with_laser = laser.len + player_radius;
-            let player_radius_with_laser_squared =
-                player_radius_with_laser * player_radius_with_laser;
-            // We are close enough to check for a collision.
-            if dist_squared < player_radius_with_laser_squared {
-                let laser_dir = locomotor.velocity.normalize();
+        let laser_iter = (
+            &entities,
+            &lasers,
+            &locomotors,
+            (&tethers).maybe(),
+        ).join();
+        for (entity, laser, locomotor, tether) in laser_iter {
+            // Find the endpoints of the laser segment. Tethered lasers span the
+            // segment between their anchors, while free lasers are centered on
+            // their position and point along their velocity.
+            let (laser_top, laser_bot) = match tether {
+                Some(tether) => {
+                    let [a, b] = tether.anchors;
+                    match (locomotors.get(a), locomotors.get(b)) {
+                        (Some(a), Some(b)) => (a.pos, b.pos),
+                        // The `TetherSystem` removes fences that have lost an
+                        // anchor.
+                        _ => continue,
+                    }
+                },
+                None => {
+                    let laser_dir = locomotor.velocity.normalize();
+
+                    let half_laser_vec = (laser.len * 0.5) * laser_dir;
+
+                    (
+                        locomotor.pos + half_laser_vec,
+                        locomotor.pos - half_laser_vec,
+                    )
+                },
+            };
+
+            let laser_pos = laser_bot + (laser_top - laser_bot) * 0.5;
+            let laser_len = (laser_top - laser_bot).norm();
 
-                let half_laser_vec = (laser.len * 0.5) * laser_dir;
+            let targets_iter = targets.iter_mut();
+            for (player_pos, bb, health, polarized, dodging) in targets_iter {
+                let player_pos = *player_pos;
+                let player_radius = bb.dist();
+                let dist_vec = player_pos.coords - laser_pos.coords;
+                let dist_squared = dist_vec.norm_squared();
+                let player_radius_with_laser =
+                    laser_len + laser.width + player_radius;
+                let player_radius_with_laser_squared =
+                    player_radius_with_laser * player_radius_with_laser;
+                // We are close enough to check for a collision.
+                if dist_squared < player_radius_with_laser_squared {
+                    // Account for the laser's width by growing the player's
+                    // box.
+                    let grown_bb = BoundingBox(
+                        bb.0 + Vec2::from_element(laser.width * 0.5),
+                    );
+                    let grown_rect =
+                        Rect::from_bounding_box(player_pos, grown_bb);
+                    if !line_intersects_rect(laser_top, laser_bot, grown_rect) {
+                        continue;
+                    }
 
-                let laser_top = laser_pos + half_laser_vec;
-                let laser_bot = laser_pos - half_laser_vec;
+                    let absorbed = match polarized {
+                        Some(polarized)
+                            if polarized.absorbs(laser.polarity) =>
+                        {
+                            polarized.absorb(laser.damage);
+                            true
+                        },
+                        _ => false,
+                    };
+                    if absorbed {
+                        stats.lasers_absorbed += 1;
+                    } else if !*dodging && health.damage(laser.damage) {
+                        stats.hits_taken += 1;
+                        if health.is_dead() {
+                            kill_cam.lethal = rewindables.get(entity)
+                                .map(|rewindable| rewindable.0);
+                        }
+                    } else {
+                        // Lasers pass straight through an invulnerable or
+                        // dashing player.
+                        continue;
+                    }
 
-                if line_intersects_rect(laser_top, laser_bot, player_rect) {
                     // Panic in case of entity deletion failure.
                     entities.delete(entity)
                         .expect("The deletion of an entity failed?");
+                    // A laser can only hit one player.
+                    break;
                 }
-            } else if dist_squared > self.despawn_dist_squared {
-                // Panic in case of entity deletion failure.
-                entities.delete(entity)
-                    .expect("The deletion of an entity failed?");
             }
         }
     }
@@ -8,15 +8,44 @@ use std::path::Path;
 
 use amethyst::core::Transform;
 use amethyst::config::{Config, ConfigError};
-use amethyst::ecs::{Entities, Join, Read, ReadStorage, System, WriteStorage};
+use amethyst::ecs::{
+    Entities,
+    Join,
+    Read,
+    ReadExpect,
+    ReadStorage,
+    System,
+    Write,
+    WriteStorage,
+};
 use amethyst::renderer::palette::Srgb;
 use amethyst::renderer::resources::Tint;
 use amethyst::renderer::SpriteRender;
 
 use serde::{Deserialize, Serialize};
 
-use crate::components::{Laser, Player, RelativeLocomotor};
-use crate::resources::SpriteMap;
+use crate::components::{
+    ForceAccumulator,
+    Health,
+    Laser,
+    Lifetime,
+    MotionKind,
+    MotionModel,
+    Player,
+    RelativeLocomotor,
+    Rewindable,
+    TimeDomain,
+};
+use crate::resources::{
+    Arena,
+    GameRng,
+    History,
+    LaserArchetypes,
+    RunStats,
+    SpriteMap,
+    LASER_SPRITE_LEN,
+    LASER_SPRITE_WIDTH,
+};
 use crate::vector::prelude::*;
 
 // TODO: hopefully remove the `Default` derivation pending this issue:
@@ -24,7 +53,13 @@ use crate::vector::prelude::*;
 #[derive(Default, Deserialize, Serialize)]
 pub struct LaserSpawnerConfig {
     spawn_rate: u32,
+    /// The distance outside of the arena at which lasers are spawned.
     spawn_dist: StorageTy,
+    /// How long each laser lives, in scaled seconds.
+    laser_lifetime: StorageTy,
+    /// The names of the archetypes from which each spawned laser picks one at
+    /// random. If this is empty, no lasers are spawned.
+    archetypes: Vec<String>,
 }
 
 pub struct LaserSpawnerSystem {
@@ -57,12 +92,23 @@ impl<'s> System<'s> for LaserSpawnerSystem {
     #[allow(clippy::type_complexity)]
     type SystemData = (
         ReadStorage<'s, Player>,
+        ReadStorage<'s, Health>,
         WriteStorage<'s, Laser>,
+        WriteStorage<'s, Lifetime>,
         WriteStorage<'s, Transform>,
         WriteStorage<'s, RelativeLocomotor>,
+        WriteStorage<'s, MotionModel>,
+        WriteStorage<'s, ForceAccumulator>,
+        WriteStorage<'s, TimeDomain>,
         WriteStorage<'s, SpriteRender>,
         WriteStorage<'s, Tint>,
+        WriteStorage<'s, Rewindable>,
         Read<'s, SpriteMap>,
+        Read<'s, LaserArchetypes>,
+        ReadExpect<'s, Arena>,
+        Write<'s, RunStats>,
+        Write<'s, GameRng>,
+        Write<'s, History>,
         Entities<'s>,
     );
 
@@ -72,41 +118,80 @@ impl<'s> System<'s> for LaserSpawnerSystem {
         // structure?
         (
             players,
+            healths,
             mut lasers,
+            mut lifetimes,
             mut transforms,
             mut locomotors,
+            mut motion_models,
+            mut accumulators,
+            mut time_domains,
             mut sprite_renderers,
             mut tints,
+            mut rewindables,
             sprite_map,
+            archetypes,
+            arena,
+            mut stats,
+            mut rng,
+            mut history,
             entities,
         ): Self::SystemData
     ) {
+        if self.config.archetypes.is_empty() {
+            return;
+        }
+
         // Increase the spawn countdown. This should definitely not be linked to
         // the update rate ans should be tied to wall time.
         self.counter += 1;
         if self.counter > self.config.spawn_rate {
-            // Get the player's locomotor.
+            // Pick a random living player to target. There is nothing to do if
+            // every player is dead.
             // TODO: is this idiomatic?
-            let player_locomotor = (&players, &locomotors)
+            let targets: Vec<_> = (&players, &locomotors, (&healths).maybe())
                 .join()
-                .next()
-                .map(|(_, l)| l) // Extract the locomotor.
-                .unwrap();
+                .filter(|(_, _, health)| health.map_or(true, |h| !h.is_dead()))
+                .map(|(_, l, _)| l.pos) // Extract the position.
+                .collect();
+            if targets.is_empty() {
+                return;
+            }
+            let player_pos = targets[rng.index(targets.len())];
 
-            // The desired spawn distance from the player.
-            // TODO: make this configurable.
-            let player_dist = 250.0;
+            // Pick one of the configured archetypes at random.
+            let names = &self.config.archetypes;
+            let name = &names[rng.index(names.len())];
+            // Explicit panic if a spawn refers to an unknown archetype, since
+            // this is a mistake in the config files.
+            let archetype = archetypes.get(name).unwrap_or_else(|| {
+                panic!("Unknown laser archetype {:?}", name)
+            });
 
             // Pick a random rotation.
-            let rand_theta = rand::random::<StorageTy>() * PI * 2.0;
+            let rand_theta = rng.unit() * PI * 2.0;
 
             // Rotate the y unit vector by our angle.
             let rotation = Rot2::new(rand_theta);
             let rotated_vec = rotation * Vec2::y();
 
             // Scale the rotation vector by the desired distance from the
-            // player, then add the scaled vector to the player's position.
-            let laser_pos = player_locomotor.pos + (player_dist * rotated_vec);
+            // arena's center, then add the scaled vector to the arena's center.
+            // The spawn distance is measured from the edge of the arena, so
+            // that lasers never appear inside it.
+            let spawn_dist = arena.bounding_radius() + self.config.spawn_dist;
+            let laser_pos = arena.center + (spawn_dist * rotated_vec);
+
+            // Point the laser at the player.
+            let to_player = player_pos - laser_pos;
+            let laser_dir = if to_player == Vec2::zeros() {
+                -rotated_vec
+            } else {
+                to_player.normalize()
+            };
+            // Sprites are drawn along the y-axis, so this is the angle that
+            // rotates the y-axis onto the laser's direction.
+            let laser_theta = (-laser_dir[0]).atan2(laser_dir[1]);
 
             // The laser translation. We take the 2D laser position vector, add
             // a dimension to it (z initialized to 0.0) and then convert it into
@@ -121,12 +206,16 @@ impl<'s> System<'s> for LaserSpawnerSystem {
             // is equal to our rotation.
             let laser_rotation = Quaternion::from_axis_angle(
                 &Vec3::z_axis(),
-                rand_theta,
+                laser_theta,
             );
 
-            // The laser scale. We don't want to scale anything, so we pass a
-            // `Vector3` of all ones.
-            let laser_scale = Vec3::from_element(1.0);
+            // The laser scale. The sprite is stretched to the archetype's
+            // dimensions.
+            let laser_scale = Vec3::new(
+                archetype.width / LASER_SPRITE_WIDTH,
+                archetype.len / LASER_SPRITE_LEN,
+                1.0,
+            );
 
             // The laser's actual transform.
             let laser_transform = Transform::new(
@@ -136,42 +225,71 @@ impl<'s> System<'s> for LaserSpawnerSystem {
             );
 
             // Extract the laser's sprite sheet from the sprite map.
-            let sprite_sheet = sprite_map.get("laser_sprite").unwrap();
+            let sprite_sheet = sprite_map.get(&archetype.sprite).unwrap();
             let sprite_renderer = SpriteRender {
                 sprite_sheet,
                 sprite_number: 0,
             };
 
-            // Determine the laser's tint color. For now, this color is chosen
-            // at random.
+            // The laser's tint color is determined by its archetype.
             // TODO: Create colored textures on the fly to avoid the "tint"
-            // look? Choose a more nuanced color selection scheme, perhaps a
-            // color representation of some frequency of a signal (e.g. sound)?
-            let laser_color = (rand::random(), rand::random(), rand::random());
+            // look?
+            let laser_color = archetype.color;
             let laser = Laser {
+                archetype: name.clone(),
                 color: laser_color,
-                // TODO: don't hardcode this.
-                len: 32.0,
+                len: archetype.len,
+                width: archetype.width,
+                damage: archetype.damage,
+                polarity: archetype.polarity,
             };
 
-            // Point the laser at the player and fire at a reasonable velocity.
+            // Fire the laser at the player.
             // TODO: some variance on the angle at which the laser is rotated
             // will likely create a more interesting experience. or, perhaps a
             // time-synchronized velocity for groups of lasers?
-            // TODO: laser speed in a config file.
-            let laser_velocity = -rotated_vec * 100.0;
+            let laser_velocity = laser_dir * archetype.speed;
 
             let laser_locomotor = RelativeLocomotor::with_velocity(laser_pos, laser_velocity);
 
+            // Linear lasers need no motion model. Orbiting models circle the
+            // player's position at spawn time.
+            let motion_model = match archetype.motion_model {
+                MotionKind::Linear => None,
+                ref kind => Some(MotionModel::new(
+                    kind.clone(),
+                    &laser_locomotor,
+                    player_pos,
+                )),
+            };
+
             // Construct the entity and add it to the scene.
-            entities.build_entity()
+            let laser_entity = entities.build_entity()
                 .with(sprite_renderer, &mut sprite_renderers)
                 .with(laser_transform, &mut transforms)
                 .with(laser_locomotor, &mut locomotors)
+                .with(ForceAccumulator::default(), &mut accumulators)
                 .with(laser, &mut lasers)
+                .with(Lifetime::new(self.config.laser_lifetime), &mut lifetimes)
                 .with(Tint(Srgb::from(laser_color).into()), &mut tints)
+                .with(Rewindable(history.next_id()), &mut rewindables)
                 .build();
 
+            if let Some(motion_model) = motion_model {
+                // Panic in case of insertion failure, which can only happen if
+                // the entity we just built is somehow dead.
+                motion_models.insert(laser_entity, motion_model)
+                    .expect("The insertion of a motion model failed?");
+            }
+
+            if let Some(name) = &archetype.time_domain {
+                // Panic in case of insertion failure, which can only happen if
+                // the entity we just built is somehow dead.
+                time_domains.insert(laser_entity, TimeDomain(name.clone()))
+                    .expect("The insertion of a time domain failed?");
+            }
+
+            stats.lasers_spawned += 1;
             self.counter = 0;
         }
     }
@@ -1,19 +1,76 @@
 //! Exports for the very limited public APIs of the systems within this module.
 
+mod arena_outline;
+pub use arena_outline::ArenaOutlineSystem;
+
+mod cleanup;
+pub use cleanup::CleanupSystem;
+
 mod debug;
 pub use debug::DebugSystem;
 
+mod fence_spawner;
+pub use fence_spawner::FenceSpawnerSystem;
+
+mod ghost;
+pub use ghost::GhostSystem;
+
+mod history;
+pub use history::HistorySystem;
+
+mod interpolation;
+pub use interpolation::InterpolationSystem;
+
+mod invulnerability;
+pub use invulnerability::InvulnerabilitySystem;
+
+mod kill_cam_recorder;
+pub use kill_cam_recorder::KillCamRecorderSystem;
+
 mod laser_collision;
 pub use laser_collision::LaserCollisionSystem;
 
 mod laser_spawner;
 pub use laser_spawner::LaserSpawnerSystem;
 
+mod overlay;
+pub use overlay::OverlaySystem;
+
+mod physics;
+pub use physics::PhysicsSystem;
+
+mod pickup_collection;
+pub use pickup_collection::PickupCollectionSystem;
+
+mod pickup_spawner;
+pub use pickup_spawner::PickupSpawnerSystem;
+
 mod player;
 pub use player::PlayerSystem;
 
+mod polarity;
+pub use polarity::PolaritySystem;
+
 mod relative_motion;
 pub use relative_motion::RelativeMotionSystem;
 
+mod shield;
+pub use shield::ShieldSystem;
+
+mod slow_time;
+pub use slow_time::SlowTimeSystem;
+
+mod tether;
+pub use tether::TetherSystem;
+
+mod rewind;
+pub use rewind::RewindSystem;
+
+mod run_stats;
+pub use run_stats::RunStatsSystem;
+
+mod threat_indicator;
+pub use threat_indicator::ThreatIndicatorSystem;
+
 mod time_scaling;
 pub use time_scaling::TimeScalingSystem;
@@ -0,0 +1,128 @@
+//! This module draws the players' raised shields, polarity meters and the
+//! shared rewind and focus meters with the debug lines renderer. It runs every
+//! frame rather than every simulation tick, since the debug lines are cleared
+//! every frame. Everything is drawn around the players' interpolated
+//! transforms, and nothing is drawn for hidden players.
+use amethyst::core::{Hidden, Transform};
+use amethyst::ecs::{Join, Read, ReadStorage, System, Write};
+use amethyst::renderer::debug_drawing::DebugLines;
+use amethyst::renderer::palette::Srgba;
+
+use crate::components::{Health, Player, Polarized, Shield};
+use crate::resources::{Focus, RewindMeter};
+use crate::vector::prelude::*;
+
+/// The number of line segments used to draw a shield's arc.
+const ARC_SEGMENTS: u32 = 12;
+/// The width of the polarity meter drawn under each player, in world units.
+const METER_WIDTH: StorageTy = 8.0;
+/// The distance from the center of each player to their polarity meter.
+const METER_OFFSET: StorageTy = 7.0;
+/// The distance between the polarity meter and the rewind meter below it.
+const METER_SPACING: StorageTy = 1.5;
+
+pub struct OverlaySystem;
+
+impl<'s> System<'s> for OverlaySystem {
+    // TODO: is this idiomatic? Can I package these in some more convenient
+    // structure?
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, Health>,
+        ReadStorage<'s, Shield>,
+        ReadStorage<'s, Polarized>,
+        ReadStorage<'s, Transform>,
+        ReadStorage<'s, Hidden>,
+        Read<'s, RewindMeter>,
+        Read<'s, Focus>,
+        Write<'s, DebugLines>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            players,
+            healths,
+            shields,
+            polarizeds,
+            transforms,
+            hiddens,
+            rewind_meter,
+            focus,
+            mut debug_lines,
+        ): Self::SystemData
+    ) {
+        let player_iter = (
+            &players,
+            &transforms,
+            (&healths).maybe(),
+            (&shields).maybe(),
+            (&polarizeds).maybe(),
+            !&hiddens,
+        ).join();
+        for (_, transform, health, shield, polarized, _) in player_iter {
+            if health.map_or(false, |health| health.is_dead()) {
+                continue;
+            }
+
+            let translation = transform.translation();
+            let center = Point2::new(translation[0], translation[1]);
+
+            // Draw the raised shield.
+            if let Some(shield) = shield.filter(|shield| shield.raised) {
+                let color = Srgba::new(0.6, 0.9, 1.0, 1.0);
+                let half_angle = shield.half_angle();
+                let radius = shield.config.radius;
+                let step =
+                    Rot2::new(2.0 * half_angle / ARC_SEGMENTS as StorageTy);
+                let mut from = Rot2::new(-half_angle) * shield.facing;
+                for _ in 0..ARC_SEGMENTS {
+                    let to = step * from;
+                    let a = center + from * radius;
+                    let b = center + to * radius;
+                    debug_lines.draw_line(
+                        Point3::new(a[0], a[1], 0.0),
+                        Point3::new(b[0], b[1], 0.0),
+                        color,
+                    );
+                    from = to;
+                }
+            }
+
+            // Draw the polarity meter, filling from left to right.
+            if let Some(polarized) = polarized {
+                let (r, g, b) = polarized.config.color(polarized.polarity);
+                let left =
+                    center + Vec2::new(-METER_WIDTH * 0.5, -METER_OFFSET);
+                let right =
+                    left + Vec2::new(METER_WIDTH * polarized.fill(), 0.0);
+                debug_lines.draw_line(
+                    Point3::new(left[0], left[1], 0.0),
+                    Point3::new(right[0], right[1], 0.0),
+                    Srgba::new(r, g, b, 1.0),
+                );
+            }
+
+            // Draw the rewind meter, which every player shares, below it.
+            let left = center
+                + Vec2::new(-METER_WIDTH * 0.5, -METER_OFFSET - METER_SPACING);
+            let right = left + Vec2::new(METER_WIDTH * rewind_meter.fill, 0.0);
+            debug_lines.draw_line(
+                Point3::new(left[0], left[1], 0.0),
+                Point3::new(right[0], right[1], 0.0),
+                Srgba::new(0.9, 0.8, 0.3, 1.0),
+            );
+
+            // Draw the focus meter below that, dimmed while exhausted.
+            let left = left - Vec2::new(0.0, METER_SPACING);
+            let right = left + Vec2::new(METER_WIDTH * focus.fill, 0.0);
+            let brightness = if focus.exhausted { 0.4 } else { 1.0 };
+            debug_lines.draw_line(
+                Point3::new(left[0], left[1], 0.0),
+                Point3::new(right[0], right[1], 0.0),
+                Srgba::new(0.9 * brightness, 0.5 * brightness, brightness, 1.0),
+            );
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! This module integrates the forces gathered in each entity's
+//! `ForceAccumulator` into its velocity. Positions are left to the
+//! `RelativeMotionSystem`, which must run afterwards.
+//!
+//! Entities are integrated with the time scale of their `TimeDomain`, like
+//! their positions are. Entities following a parametric `MotionModel` ignore
+//! forces, since their model dictates exactly where they are.
+//!
+//! An entity that is mid-dash is exempt from its `MaxSpeed`. Velocity changes
+//! are passed on to differential motion models, since Verlet integration
+//! derives the velocity from the previous position rather than storing it.
+use amethyst::core::Time;
+use amethyst::ecs::{Join, Read, ReadStorage, System, WriteStorage};
+
+use crate::components::{
+    Dash,
+    Drag,
+    ForceAccumulator,
+    Mass,
+    MaxSpeed,
+    MotionModel,
+    RelativeLocomotor,
+    TimeDomain,
+};
+use crate::resources::TimeChannels;
+use crate::vector::prelude::*;
+
+pub struct PhysicsSystem;
+
+impl<'s> System<'s> for PhysicsSystem {
+    // TODO: is this idiomatic? Can I package these in some more convenient
+    // structure?
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadStorage<'s, TimeDomain>,
+        ReadStorage<'s, Mass>,
+        ReadStorage<'s, Drag>,
+        ReadStorage<'s, MaxSpeed>,
+        ReadStorage<'s, Dash>,
+        WriteStorage<'s, MotionModel>,
+        WriteStorage<'s, ForceAccumulator>,
+        WriteStorage<'s, RelativeLocomotor>,
+        Read<'s, TimeChannels>,
+        Read<'s, Time>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            time_domains,
+            masses,
+            drags,
+            max_speeds,
+            dashes,
+            mut motion_models,
+            mut accumulators,
+            mut locomotors,
+            channels,
+            time,
+        ): Self::SystemData
+    ) {
+        let entity_iter = (
+            &mut accumulators,
+            &mut locomotors,
+            (&time_domains).maybe(),
+            (&masses).maybe(),
+            (&drags).maybe(),
+            (&max_speeds).maybe(),
+            (&dashes).maybe(),
+            (&mut motion_models).maybe(),
+        ).join();
+        for (
+            accumulator,
+            locomotor,
+            time_domain,
+            mass,
+            drag,
+            max_speed,
+            dash,
+            motion_model,
+        ) in entity_iter {
+            let parametric = motion_model.as_ref()
+                .map_or(false, |model| model.is_parametric());
+            if parametric {
+                accumulator.clear();
+                continue;
+            }
+
+            let delta = channels.scale(time_domain) * time.fixed_seconds();
+
+            let mass = mass.copied().unwrap_or_default().0;
+            let drag = drag.map_or(0.0, |drag| drag.0);
+
+            let acceleration =
+                accumulator.force / mass - locomotor.velocity * drag;
+            let velocity = locomotor.velocity
+                + acceleration * delta
+                + accumulator.impulse / mass;
+
+            // Ensure our velocity does not exceed our max speed. If it does, we
+            // clamp it down. Dashing lets us go faster for a short while.
+            let dashing = dash.map_or(false, |dash| dash.is_active());
+            let velocity = match max_speed {
+                Some(&MaxSpeed(max_speed))
+                    if !dashing
+                        && velocity.norm_squared() > max_speed * max_speed =>
+                {
+                    velocity.normalize() * max_speed
+                },
+                _ => velocity,
+            };
+
+            if let Some(model) = motion_model {
+                model.shift_velocity(velocity - locomotor.velocity);
+            }
+            locomotor.velocity = velocity;
+            accumulator.clear();
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! This module is responsible for collecting pickups. A pickup is collected by
+//! the first living player whose bounding box overlaps its own, at which point
+//! its effect is applied and it is removed.
+use amethyst::ecs::{
+    Entities,
+    Join,
+    ReadStorage,
+    System,
+    Write,
+    WriteStorage,
+};
+
+use crate::collisions::box_collision::*;
+use crate::components::{
+    BoundingBox,
+    Health,
+    Laser,
+    Pickup,
+    PickupKind,
+    Player,
+    RelativeLocomotor,
+    Shield,
+};
+use crate::resources::{Focus, RunStats, SlowTime};
+
+pub struct PickupCollectionSystem;
+
+impl<'s> System<'s> for PickupCollectionSystem {
+    // TODO: is this idiomatic? Can I package these in some more convenient
+    // structure?
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadStorage<'s, Pickup>,
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, Laser>,
+        ReadStorage<'s, BoundingBox>,
+        ReadStorage<'s, RelativeLocomotor>,
+        WriteStorage<'s, Health>,
+        WriteStorage<'s, Shield>,
+        Write<'s, RunStats>,
+        Write<'s, SlowTime>,
+        Write<'s, Focus>,
+        Entities<'s>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            pickups,
+            players,
+            lasers,
+            bounding_boxes,
+            locomotors,
+            mut healths,
+            mut shields,
+            mut stats,
+            mut slow_time,
+            mut focus,
+            entities,
+        ): Self::SystemData
+    ) {
+        let mut bomb = false;
+
+        let pickup_iter =
+            (&entities, &pickups, &bounding_boxes, &locomotors).join();
+        for (entity, pickup, &pickup_bb, pickup_locomotor) in pickup_iter {
+            let pickup_rect =
+                Rect::from_bounding_box(pickup_locomotor.pos, pickup_bb);
+
+            let player_iter = (
+                &entities,
+                &players,
+                &bounding_boxes,
+                &locomotors,
+                &healths,
+            ).join();
+            let collector = player_iter
+                .filter(|(_, _, _, _, health)| !health.is_dead())
+                .find(|(_, _, &bb, locomotor, _)| rect_intersects_rect(
+                    pickup_rect,
+                    Rect::from_bounding_box(locomotor.pos, bb),
+                ))
+                .map(|(player, _, _, _, _)| player);
+            let collector = match collector {
+                Some(collector) => collector,
+                None => continue,
+            };
+
+            match pickup.kind {
+                PickupKind::Score { points } => {
+                    stats.score += points;
+                },
+                PickupKind::Health { amount } => {
+                    if let Some(health) = healths.get_mut(collector) {
+                        health.current =
+                            (health.current + amount).min(health.max);
+                    }
+                },
+                PickupKind::SlowTime { duration, time_scale } => {
+                    // A new slow time pickup replaces any running one.
+                    *slow_time = SlowTime {
+                        remaining: duration,
+                        max_time_scale: time_scale,
+                    };
+                },
+                PickupKind::Focus { amount } => {
+                    focus.fill = (focus.fill + amount).min(1.0);
+                },
+                PickupKind::Shield { duration } => {
+                    if let Some(shield) = shields.get_mut(collector) {
+                        shield.boosted_for = shield.boosted_for.max(duration);
+                    }
+                },
+                PickupKind::Bomb => bomb = true,
+            }
+
+            // Panic in case of entity deletion failure.
+            entities.delete(entity)
+                .expect("The deletion of an entity failed?");
+        }
+
+        if bomb {
+            for (entity, _) in (&entities, &lasers).join() {
+                // Panic in case of entity deletion failure.
+                entities.delete(entity)
+                    .expect("The deletion of an entity failed?");
+            }
+        }
+    }
+}
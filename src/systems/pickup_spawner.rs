@@ -0,0 +1,214 @@
+//! This module is responsible for spawning pickups. Pickups appear at random
+//! positions inside the region of the world visible through the camera, and
+//! stay in place until they are collected or their lifetime expires. They can
+//! be pushed around by forces, but drag brings them back to rest.
+use std::path::Path;
+
+use amethyst::config::{Config, ConfigError};
+use amethyst::core::{Time, Transform};
+use amethyst::ecs::{
+    Entities,
+    Join,
+    Read,
+    ReadStorage,
+    System,
+    Write,
+    WriteStorage,
+};
+use amethyst::renderer::palette::Srgb;
+use amethyst::renderer::resources::Tint;
+use amethyst::renderer::SpriteRender;
+use amethyst::utils::ortho_camera::CameraOrtho;
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::{
+    BoundingBox,
+    Drag,
+    ForceAccumulator,
+    Lifetime,
+    Pickup,
+    PickupKind,
+    RelativeLocomotor,
+    Rewindable,
+};
+use crate::resources::{
+    GameRng,
+    History,
+    SpriteMap,
+    TimeScale,
+    LASER_SPRITE_LEN,
+    LASER_SPRITE_WIDTH,
+};
+use crate::vector::prelude::*;
+
+/// A kind of pickup that may be spawned, along with its appearance.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PickupSpec {
+    kind: PickupKind,
+    color: (f32, f32, f32),
+}
+
+// TODO: hopefully remove the `Default` derivation pending this issue:
+// https://github.com/amethyst/amethyst/issues/1954
+#[derive(Default, Deserialize, Serialize)]
+pub struct PickupSpawnerConfig {
+    /// The time between pickup spawns, in scaled seconds.
+    spawn_interval: StorageTy,
+    /// How long each pickup lives, in scaled seconds.
+    lifetime: StorageTy,
+    /// The side length of each pickup's square.
+    size: StorageTy,
+    /// The pickups' `Drag`.
+    drag: StorageTy,
+    /// The distance from the edge of the visible region inside which pickups
+    /// are never spawned.
+    inset: StorageTy,
+    /// The pickups from which each spawn picks one at random. If this is empty,
+    /// no pickups are spawned.
+    pickups: Vec<PickupSpec>,
+}
+
+pub struct PickupSpawnerSystem {
+    /// The scaled time since the last spawn.
+    timer: StorageTy,
+    config: PickupSpawnerConfig,
+}
+
+impl PickupSpawnerSystem {
+    /// Builds a `PickupSpawnerSystem` with the provided `PickupSpawnerConfig`.
+    pub fn from_config(config: impl Into<PickupSpawnerConfig>) -> Self {
+        Self {
+            timer: 0.0,
+            config: config.into(),
+        }
+    }
+
+    /// Builds a `PickupSpawnerSystem` by reading the RON file at `path`.
+    pub fn from_config_path(
+        path: impl AsRef<Path>
+    ) -> Result<Self, ConfigError> {
+        // TODO: hopefully change this to just call load pending this issue:
+        // https://github.com/amethyst/amethyst/issues/1954
+        PickupSpawnerConfig::load_no_fallback(path).map(Self::from_config)
+    }
+}
+
+impl<'s> System<'s> for PickupSpawnerSystem {
+    // TODO: is this idiomatic? Can I package these in some more convenient
+    // structure?
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadStorage<'s, CameraOrtho>,
+        WriteStorage<'s, Pickup>,
+        WriteStorage<'s, BoundingBox>,
+        WriteStorage<'s, Lifetime>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, RelativeLocomotor>,
+        WriteStorage<'s, ForceAccumulator>,
+        WriteStorage<'s, Drag>,
+        WriteStorage<'s, SpriteRender>,
+        WriteStorage<'s, Tint>,
+        WriteStorage<'s, Rewindable>,
+        Read<'s, SpriteMap>,
+        Read<'s, TimeScale>,
+        Read<'s, Time>,
+        Write<'s, GameRng>,
+        Write<'s, History>,
+        Entities<'s>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            cameras,
+            mut pickups,
+            mut bounding_boxes,
+            mut lifetimes,
+            mut transforms,
+            mut locomotors,
+            mut accumulators,
+            mut drags,
+            mut sprite_renderers,
+            mut tints,
+            mut rewindables,
+            sprite_map,
+            time_scale,
+            time,
+            mut rng,
+            mut history,
+            entities,
+        ): Self::SystemData
+    ) {
+        if self.config.pickups.is_empty() {
+            return;
+        }
+
+        self.timer += time_scale.0 * time.fixed_seconds();
+        if self.timer < self.config.spawn_interval {
+            return;
+        }
+
+        // Find the visible region as `(min, max)` corners. There is nowhere to
+        // spawn without a camera, so try again next frame.
+        let inset = self.config.inset;
+        let (min, max) = match (&cameras, &transforms).join().next() {
+            Some((camera, transform)) => {
+                let center = transform.translation();
+                let coords = &camera.world_coordinates;
+                (
+                    Point2::new(
+                        center[0] + coords.left + inset,
+                        center[1] + coords.bottom + inset,
+                    ),
+                    Point2::new(
+                        center[0] + coords.right - inset,
+                        center[1] + coords.top - inset,
+                    ),
+                )
+            },
+            None => return,
+        };
+        self.timer = 0.0;
+
+        // Pick a random pickup and a random position for it.
+        let specs = &self.config.pickups;
+        let spec = &specs[rng.index(specs.len())];
+        let pos = min + (max - min).component_mul(&Vec2::new(
+            rng.unit(),
+            rng.unit(),
+        ));
+
+        // Pickups are drawn as squares by squashing the laser sprite.
+        let size = self.config.size;
+        let mut transform = Transform::from(add_dim(pos.coords));
+        transform.set_scale(Vec3::new(
+            size / LASER_SPRITE_WIDTH,
+            size / LASER_SPRITE_LEN,
+            1.0,
+        ));
+        // Rotate the square into a diamond, to tell it apart from anchors.
+        transform.set_rotation_2d(PI * 0.25);
+
+        // TODO: Make this type checked by having some kind of enum system for
+        // hardcoded texture ids?
+        let sprite_sheet = sprite_map.get("laser_sprite").unwrap();
+        let bounding_box = BoundingBox::from(Vec2::from_element(size * 0.5));
+
+        entities.build_entity()
+            .with(SpriteRender {
+                sprite_sheet,
+                sprite_number: 0,
+            }, &mut sprite_renderers)
+            .with(transform, &mut transforms)
+            .with(RelativeLocomotor::with_pos(pos), &mut locomotors)
+            .with(ForceAccumulator::default(), &mut accumulators)
+            .with(Drag(self.config.drag), &mut drags)
+            .with(bounding_box, &mut bounding_boxes)
+            .with(Lifetime::new(self.config.lifetime), &mut lifetimes)
+            .with(Pickup { kind: spec.kind }, &mut pickups)
+            .with(Tint(Srgb::from(spec.color).into()), &mut tints)
+            .with(Rewindable(history.next_id()), &mut rewindables)
+            .build();
+    }
+}
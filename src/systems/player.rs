@@ -1,11 +1,24 @@
-//! This module updates the player's position response to input via a (very)
-//! rudimentary twice differentiable physics model.
+//! This module pushes the players around in response to input. Input is
+//! applied as a force on each player's `ForceAccumulator`, which the
+//! `PhysicsSystem` integrates along with the player's drag and maximum speed.
+//! Each player reads their own set of input bindings.
+//!
+//! The player may also dash, which sets their velocity to a burst of speed in
+//! the input direction. While the dash lasts, the usual maximum speed is not
+//! enforced, and the player is briefly invulnerable.
 
 use amethyst::core::{Time, Transform};
 use amethyst::ecs::{Join, Read, ReadStorage, System, WriteStorage};
-use amethyst::input::{InputHandler, StringBindings};
 
-use crate::components::{Player, RelativeLocomotor};
+use crate::components::{
+    Dash,
+    ForceAccumulator,
+    Health,
+    Mass,
+    Player,
+    RelativeLocomotor,
+};
+use crate::resources::TickInput;
 use crate::vector::prelude::*;
 
 pub struct PlayerSystem;
@@ -16,9 +29,13 @@ impl<'s> System<'s> for PlayerSystem {
     #[allow(clippy::type_complexity)]
     type SystemData = (
         ReadStorage<'s, Player>,
+        ReadStorage<'s, Mass>,
+        WriteStorage<'s, ForceAccumulator>,
         WriteStorage<'s, Transform>,
         WriteStorage<'s, RelativeLocomotor>,
-        Read<'s, InputHandler<StringBindings>>,
+        WriteStorage<'s, Dash>,
+        ReadStorage<'s, Health>,
+        Read<'s, TickInput>,
         Read<'s, Time>,
     );
 
@@ -26,64 +43,97 @@ impl<'s> System<'s> for PlayerSystem {
         &mut self,
         (
             players,
+            masses,
+            mut accumulators,
             mut transforms,
             mut locomotors,
+            mut dashes,
+            healths,
             input,
             time
         ): Self::SystemData
     ) {
-        let player_iter = (&players, &mut transforms, &mut locomotors).join();
-        for (player, transform, locomotor) in player_iter {
+        let player_iter = (
+            &players,
+            &mut accumulators,
+            &mut transforms,
+            &mut locomotors,
+            &mut dashes,
+            (&healths).maybe(),
+            (&masses).maybe(),
+        ).join();
+        for (
+            player,
+            accumulator,
+            transform,
+            locomotor,
+            dash,
+            health,
+            mass,
+        ) in player_iter {
             // We must borrow here, since we can not move `config` out of
             // `player`. This is fine, since all the values we are using from
             // the config are `Copy` anyway.
             let config = &player.config;
 
-            // Grab the raw input values.
-            let x_in = input.axis_value("x_in");
-            let y_in = input.axis_value("y_in");
+            // Dead players no longer move.
+            if health.map_or(false, |health| health.is_dead()) {
+                locomotor.velocity = Vec2::zeros();
+                accumulator.clear();
+                continue;
+            }
+
+            // Grab the raw input values from this player's bindings.
+            let x_in = input.axis_value(&player.binding("x_in"));
+            let y_in = input.axis_value(&player.binding("y_in"));
 
             // Unwrap them, defaulting to 0.0 if the axis is in deadzone.
             let input_vec = Vec2::new(x_in.unwrap_or(0.0), y_in.unwrap_or(0.0));
 
-            // Normalize the input vector. If the input vector is (0.0, 0.0), we
-            // skip normalization, as the magnitude of the vector is 0.0.
-            let norm = if input_vec == Vec2::zeros() {
-                input_vec
-            } else {
-                input_vec.normalize()
-            };
-
-            // Figure out the static deceleration, meaning the force in response
-            // to "friction".
-            let scaled_decel_factor =
-                config.deceleration * time.delta_seconds();
-            let static_decel = -locomotor.velocity * scaled_decel_factor;
+            // Apply the deadzones and response curve. The magnitude of the
+            // result is kept, so that partial deflection gives partial
+            // acceleration.
+            let analog_in = config.analog.process(input_vec);
 
-            let scaled_accel_factor =
-                config.acceleration * time.delta_seconds();
-            let v_delta = norm * scaled_accel_factor + static_decel;
+            // Input is just another force. It is scaled by the player's mass,
+            // so that the configured acceleration is reached regardless of it.
+            // Friction and the maximum speed are handled by the player's `Drag`
+            // and `MaxSpeed`.
+            let mass = mass.copied().unwrap_or_default().0;
+            accumulator.add_force(analog_in * (config.acceleration * mass));
 
-            // Our new velocity, before accounting for max speed.
-            let new_velocity = locomotor.velocity + v_delta;
+            // Count down the dash timers in real time.
+            let real_delta = time.fixed_seconds();
+            dash.active_for = (dash.active_for - real_delta).max(0.0);
+            dash.cooldown_for = (dash.cooldown_for - real_delta).max(0.0);
+            dash.invulnerable_for =
+                (dash.invulnerable_for - real_delta).max(0.0);
 
-            // TODO: is this idiomatic?
-            let new_velocity_norm_squared = new_velocity.norm_squared();
-            let max_speed_squared = config.max_speed * config.max_speed;
-
-            // Ensure our velocity does not exceed our max speed. If it does, we
-            // clamp it down.
-            let velocity =
-                if new_velocity_norm_squared > max_speed_squared {
-                    new_velocity.normalize() * config.max_speed
-                } else if new_velocity_norm_squared < -max_speed_squared {
-                    new_velocity.normalize() * -config.max_speed
+            // Dash when the input is first pressed, if the cooldown allows it.
+            let dash_held = input.action_is_down(&player.binding("dash"))
+                .unwrap_or(false);
+            let dash_pressed = dash_held && !dash.was_held;
+            dash.was_held = dash_held;
+            if dash_pressed && dash.is_ready() {
+                // Dash in the input direction, falling back to our current
+                // direction of travel if there is no input.
+                let dash_dir = if analog_in != Vec2::zeros() {
+                    Some(analog_in.normalize())
+                } else if locomotor.velocity != Vec2::zeros() {
+                    Some(locomotor.velocity.normalize())
                 } else {
-                    new_velocity
+                    None
                 };
 
-            // Set the velocity to our calculated value.
-            locomotor.velocity = velocity;
+                if let Some(dash_dir) = dash_dir {
+                    locomotor.velocity = dash_dir * config.dash_speed;
+                    dash.active_for = config.dash_duration;
+                    dash.cooldown_for = config.dash_cooldown;
+                    dash.invulnerable_for = config.dash_invulnerability;
+                }
+            }
+
+            let velocity = locomotor.velocity;
 
             // Rotate the player's sprite to look left or right once the
             // player's x-velocity passes some threshold of speed in the other
@@ -0,0 +1,58 @@
+//! This module switches the players' polarities in response to input, and
+//! shows each player's polarity by tinting them with its color. The meter
+//! charged by absorbing lasers is drawn by the `OverlaySystem`.
+use amethyst::ecs::{Join, Read, ReadStorage, System, WriteStorage};
+use amethyst::renderer::palette::Srgba;
+use amethyst::renderer::resources::Tint;
+
+use crate::components::{Health, Player, Polarized};
+use crate::resources::TickInput;
+
+pub struct PolaritySystem;
+
+impl<'s> System<'s> for PolaritySystem {
+    // TODO: is this idiomatic? Can I package these in some more convenient
+    // structure?
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, Health>,
+        WriteStorage<'s, Polarized>,
+        WriteStorage<'s, Tint>,
+        Read<'s, TickInput>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            players,
+            healths,
+            mut polarizeds,
+            mut tints,
+            input,
+        ): Self::SystemData
+    ) {
+        let player_iter = (
+            &players,
+            &mut polarizeds,
+            &mut tints,
+            (&healths).maybe(),
+        ).join();
+        for (player, polarized, tint, health) in player_iter {
+            let alive = health.map_or(true, |health| !health.is_dead());
+
+            // The polarity switches when the input is first pressed.
+            let held = input.action_is_down(&player.binding("polarity"))
+                .unwrap_or(false);
+            if alive && held && !polarized.was_held {
+                polarized.polarity = polarized.polarity.toggled();
+            }
+            polarized.was_held = held;
+
+            // Tint the player's own color with the color of their polarity.
+            let (r, g, b) = player.slot.color;
+            let (pr, pg, pb) = polarized.config.color(polarized.polarity);
+            tint.0 = Srgba::new(r * pr, g * pg, b * pb, 1.0);
+        }
+    }
+}
@@ -1,26 +1,89 @@
 //! The `RelativeMotionSystem` is used to move every entity with a velocity and
-//! a transform according to the current time scale of the world. This is an
-//! abstraction to combine motion updates with the current time scale to avoid
-//! the possibility of updating in its absence.
+//! a transform according to the current time scale of its `TimeDomain`. This
+//! is an abstraction to combine motion updates with the current time scale to
+//! avoid the possibility of updating in its absence.
+//!
+//! Players are confined to the `Arena`, sliding along its edges.
+//!
+//! Entities with a `MotionModel` are moved according to that model instead of
+//! at constant velocity. Their sprites are also rotated to face along their
+//! velocity, since their direction of travel may change over time.
+//!
+//! Differential models are advanced with the configured `Integrator`. When a
+//! tick covers more scaled time than the configured maximum step, it is split
+//! into several equal sub-steps.
+use std::path::Path;
 
 use amethyst::{
+    config::{Config, ConfigError},
     core::timing::Time,
     core::transform::Transform,
-    ecs::prelude::{Join, Read, System, WriteStorage},
+    ecs::prelude::{Join, Read, ReadExpect, ReadStorage, System, WriteStorage},
 };
 
-use crate::components::RelativeLocomotor;
-use crate::resources::TimeScale;
+use serde::{Deserialize, Serialize};
 
-pub struct RelativeMotionSystem;
+use crate::components::{
+    BoundingBox,
+    Health,
+    MotionModel,
+    Player,
+    RelativeLocomotor,
+    TimeDomain,
+};
+use crate::integration::Integrator;
+use crate::resources::{Arena, TimeChannels};
+use crate::vector::prelude::*;
+
+// TODO: hopefully remove the `Default` derivation pending this issue:
+// https://github.com/amethyst/amethyst/issues/1954
+#[derive(Default, Deserialize, Serialize)]
+pub struct RelativeMotionConfig {
+    /// The integrator used for differential motion models.
+    integrator: Integrator,
+    /// The longest step, in scaled seconds, that differential models are
+    /// integrated over at once. If this is not positive, every tick is a
+    /// single step.
+    max_step: StorageTy,
+}
+
+pub struct RelativeMotionSystem {
+    config: RelativeMotionConfig,
+}
+
+impl RelativeMotionSystem {
+    /// Builds a `RelativeMotionSystem` with the provided
+    /// `RelativeMotionConfig`.
+    pub fn from_config(config: impl Into<RelativeMotionConfig>) -> Self {
+        Self {
+            config: config.into(),
+        }
+    }
+
+    /// Builds a `RelativeMotionSystem` by reading the RON file at `path`.
+    pub fn from_config_path(
+        path: impl AsRef<Path>
+    ) -> Result<Self, ConfigError> {
+        // TODO: hopefully change this to just call load pending this issue:
+        // https://github.com/amethyst/amethyst/issues/1954
+        RelativeMotionConfig::load_no_fallback(path).map(Self::from_config)
+    }
+}
 
 impl<'s> System<'s> for RelativeMotionSystem {
     // TODO: is this idiomatic? Can I package these in some more convenient
     // structure?
+    #[allow(clippy::type_complexity)]
     type SystemData = (
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, BoundingBox>,
+        ReadStorage<'s, Health>,
+        ReadStorage<'s, TimeDomain>,
         WriteStorage<'s, RelativeLocomotor>,
+        WriteStorage<'s, MotionModel>,
         WriteStorage<'s, Transform>,
-        Read<'s, TimeScale>,
+        ReadExpect<'s, Arena>,
+        Read<'s, TimeChannels>,
         Read<'s, Time>,
     );
 
@@ -29,29 +92,142 @@ impl<'s> System<'s> for RelativeMotionSystem {
         // TODO: is this idiomatic? Can I package these in some more convenient
         // structure?
         (
+            players,
+            bounding_boxes,
+            healths,
+            time_domains,
             mut locomotors,
+            mut motion_models,
             mut transforms,
-            time_scale,
+            arena,
+            channels,
             time
         ): Self::SystemData
     ) {
-        let entity_iter = (&mut locomotors, &mut transforms).join();
-        for (locomotor, transform) in entity_iter {
-            // We simply multiply the current time scale by the amount of time
-            // that passed.
-            let scaled_time = time_scale.0 * time.delta_seconds();
+        // Homing models steer towards the nearest living player's position at
+        // the start of this frame.
+        // TODO: is this idiomatic?
+        let player_iter = (&players, &locomotors, (&healths).maybe()).join();
+        let player_positions: Vec<_> = player_iter
+            .filter(|(_, _, health)| health.map_or(true, |h| !h.is_dead()))
+            .map(|(_, l, _)| l.pos)
+            .collect();
+
+        let entity_iter = (
+            &mut locomotors,
+            (&mut motion_models).maybe(),
+            &mut transforms,
+            (&players).maybe(),
+            (&bounding_boxes).maybe(),
+            (&time_domains).maybe(),
+        ).join();
+        for (
+            locomotor,
+            motion_model,
+            transform,
+            player,
+            bb,
+            time_domain,
+        ) in entity_iter {
+            // We simply multiply the time scale of the entity's channel by the
+            // amount of time that passed.
+            let scale = channels.scale(time_domain);
+            let scaled_time = scale * time.fixed_seconds();
+
+            let (new_pos, new_velocity) = match motion_model {
+                Some(model) => {
+                    model.elapsed += scaled_time;
+                    if model.is_homing() {
+                        let nearest = player_positions.iter()
+                            .min_by(|a, b| {
+                                let pos = locomotor.pos;
+                                let dist_a = (**a - pos).norm_squared();
+                                let dist_b = (**b - pos).norm_squared();
+                                dist_a.partial_cmp(&dist_b)
+                                    .unwrap_or(std::cmp::Ordering::Equal)
+                            });
+                        if let Some(&target) = nearest {
+                            model.retarget(target);
+                        }
+                    }
+                    let (pos, velocity) = match model.evaluate(model.elapsed) {
+                        // Parametric models tell us exactly where to be.
+                        Some(state) => state,
+                        // Differential models are integrated step by step.
+                        None => {
+                            let config = &self.config;
+                            // Time that does not move leaves no step behind
+                            // for Verlet integration to build on.
+                            let steps = if scaled_time <= 0.0 {
+                                0
+                            } else if config.max_step > 0.0 {
+                                (scaled_time / config.max_step).ceil().max(1.0)
+                                    as u32
+                            } else {
+                                1
+                            };
+                            let h = scaled_time / steps.max(1) as StorageTy;
+
+                            let integrator = config.integrator;
+                            let mut pos = locomotor.pos;
+                            let mut velocity = locomotor.velocity;
+                            for _ in 0..steps {
+                                let (new_pos, new_velocity) = integrator.step(
+                                    pos,
+                                    velocity,
+                                    model.prev,
+                                    h,
+                                    |p, v| model.acceleration(p, v),
+                                );
+                                // Constraining the velocity moves the previous
+                                // position along with it, like any other
+                                // change made outside of integration.
+                                velocity =
+                                    model.constrain_velocity(new_velocity);
+                                model.prev = Some((pos, h));
+                                model.shift_velocity(velocity - new_velocity);
+                                pos = new_pos;
+                            }
+
+                            (pos, velocity)
+                        },
+                    };
+
+                    // Face the sprite along the direction of travel. Sprites
+                    // are drawn along the y-axis.
+                    if velocity != Vec2::zeros() {
+                        transform.set_rotation_2d(
+                            (-velocity[0]).atan2(velocity[1]),
+                        );
+                    }
+
+                    (pos, velocity)
+                },
+                // We calculate the entity's new position, scaling by the time
+                // that passed.
+                None => (
+                    locomotor.pos + (locomotor.velocity * scaled_time),
+                    locomotor.velocity,
+                ),
+            };
 
-            // We calculate the entity's new position, scaling by the time that
-            // passed.
-            let new_pos = locomotor.pos + (locomotor.velocity * scaled_time);
+            // Players may not leave the arena. Their bounding box is kept
+            // entirely inside it.
+            let (new_pos, new_velocity) = if player.is_some() {
+                let inset = bb.map_or(0.0, |bb| bb.0[0].max(bb.0[1]));
+                arena.confine(new_pos, new_velocity, inset)
+            } else {
+                (new_pos, new_velocity)
+            };
 
             // We update the entity's transform.
             transform.set_translation(new_pos.into());
 
-            // Now we update the `old_pos` and `pos` fields on the relative
-            // locomotor.
+            // Now we update the `old_pos`, `pos` and `velocity` fields on the
+            // relative locomotor.
             locomotor.old_pos = locomotor.pos;
             locomotor.pos = new_pos;
+            locomotor.velocity = new_velocity;
         }
     }
 }
@@ -0,0 +1,257 @@
+//! This module plays the `History` backwards. It runs in place of the
+//! simulation while the world is being rewound, undoing a configurable number
+//! of recorded ticks per tick and draining the `RewindMeter`.
+//!
+//! Restoring a snapshot brings every recorded entity back to its recorded
+//! state. Entities that were deleted since are rebuilt from the snapshot, and
+//! entities spawned since are deleted. Players are never deleted, since they
+//! are recorded from the start of the run. The players' health and meters, the
+//! score and the time scaling meters are restored along with the entities.
+use std::collections::{HashMap, HashSet};
+
+use amethyst::core::{Time, Transform};
+use amethyst::ecs::{
+    Component,
+    Entities,
+    Entity,
+    Join,
+    Read,
+    ReadStorage,
+    System,
+    Write,
+    WriteStorage,
+};
+use amethyst::renderer::resources::Tint;
+use amethyst::renderer::SpriteRender;
+
+use crate::components::{
+    BoundingBox,
+    Dash,
+    Drag,
+    ForceAccumulator,
+    Health,
+    Laser,
+    Lifetime,
+    MotionModel,
+    Pickup,
+    Player,
+    Polarized,
+    RelativeLocomotor,
+    Rewindable,
+    Shield,
+    Tether,
+    TimeDomain,
+};
+use crate::resources::{
+    Focus,
+    History,
+    KillCamRecording,
+    RewindMeter,
+    RunStats,
+    SlowTime,
+};
+use crate::rewind::RewindConfig;
+
+/// Inserts `component` for `entity` if there is one, and removes any existing
+/// component otherwise.
+fn restore<C: Component>(
+    storage: &mut WriteStorage<C>,
+    entity: Entity,
+    component: Option<C>,
+) {
+    match component {
+        Some(component) => {
+            // Panic in case of insertion failure, which can only happen if
+            // the entity is somehow dead.
+            storage.insert(entity, component)
+                .expect("The insertion of a restored component failed?");
+        },
+        None => {
+            storage.remove(entity);
+        },
+    }
+}
+
+pub struct RewindSystem {
+    config: RewindConfig,
+}
+
+impl RewindSystem {
+    /// Builds a `RewindSystem` with the provided `RewindConfig`.
+    pub fn from_config(config: impl Into<RewindConfig>) -> Self {
+        Self {
+            config: config.into(),
+        }
+    }
+}
+
+impl<'s> System<'s> for RewindSystem {
+    // TODO: is this idiomatic? Can I package these in some more convenient
+    // structure?
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadStorage<'s, Player>,
+        WriteStorage<'s, Rewindable>,
+        WriteStorage<'s, RelativeLocomotor>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, Laser>,
+        WriteStorage<'s, MotionModel>,
+        WriteStorage<'s, Lifetime>,
+        WriteStorage<'s, Tether>,
+        WriteStorage<'s, TimeDomain>,
+        WriteStorage<'s, Pickup>,
+        WriteStorage<'s, BoundingBox>,
+        WriteStorage<'s, Drag>,
+        WriteStorage<'s, ForceAccumulator>,
+        WriteStorage<'s, SpriteRender>,
+        WriteStorage<'s, Tint>,
+        WriteStorage<'s, Health>,
+        WriteStorage<'s, Shield>,
+        WriteStorage<'s, Dash>,
+        WriteStorage<'s, Polarized>,
+        Write<'s, History>,
+        Write<'s, KillCamRecording>,
+        Write<'s, RewindMeter>,
+        Write<'s, RunStats>,
+        Write<'s, Focus>,
+        Write<'s, SlowTime>,
+        Read<'s, Time>,
+        Entities<'s>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            players,
+            mut rewindables,
+            mut locomotors,
+            mut transforms,
+            mut lasers,
+            mut motion_models,
+            mut lifetimes,
+            mut tethers,
+            mut time_domains,
+            mut pickups,
+            mut bounding_boxes,
+            mut drags,
+            mut accumulators,
+            mut sprite_renderers,
+            mut tints,
+            mut healths,
+            mut shields,
+            mut dashes,
+            mut polarizeds,
+            mut history,
+            mut kill_cam,
+            mut meter,
+            mut stats,
+            mut focus,
+            mut slow_time,
+            time,
+            entities,
+        ): Self::SystemData
+    ) {
+        if self.config.max_duration > 0.0 {
+            let drained = time.fixed_seconds() / self.config.max_duration;
+            meter.fill = (meter.fill - drained).max(0.0);
+        } else {
+            meter.fill = 0.0;
+        }
+
+        // The latest snapshot is the current state when a rewind starts, so it
+        // is skipped. The kill-cam keeps its frame, since that frame is still
+        // the one of the current state.
+        history.skip_current();
+
+        // Undo the configured number of ticks, keeping the oldest of them. The
+        // kill-cam forgets the undone ticks as well, and they no longer count
+        // as survived.
+        let mut snapshot = None;
+        for _ in 0..self.config.speed.max(1) {
+            kill_cam.pop();
+            match history.pop() {
+                Some(popped) => {
+                    snapshot = Some(popped);
+                    stats.survived =
+                        (stats.survived - time.fixed_seconds()).max(0.0);
+                },
+                None => break,
+            }
+        }
+        let snapshot = match snapshot {
+            Some(snapshot) => snapshot,
+            None => return,
+        };
+
+        stats.score = snapshot.score;
+        *focus = snapshot.focus;
+        *slow_time = snapshot.slow_time;
+        let snapshot = snapshot.entities;
+
+        let mut living: HashMap<u64, Entity> = (&entities, &rewindables).join()
+            .map(|(entity, rewindable)| (rewindable.0, entity))
+            .collect();
+
+        // Delete everything spawned since the snapshot was recorded.
+        let recorded: HashSet<u64> = snapshot.iter()
+            .map(|record| record.id)
+            .collect();
+        living.retain(|id, &mut entity| {
+            if recorded.contains(id) || players.contains(entity) {
+                return true;
+            }
+            // Panic in case of entity deletion failure.
+            entities.delete(entity)
+                .expect("The deletion of an entity failed?");
+            false
+        });
+
+        // Rebuild the entities deleted since, so that every recorded entity
+        // exists before tethers are restored.
+        for record in snapshot.iter() {
+            living.entry(record.id).or_insert_with(|| {
+                let entity = entities.create();
+                // Panic in case of insertion failure, which can only happen if
+                // the entity we just created is somehow dead.
+                rewindables.insert(entity, Rewindable(record.id))
+                    .expect("The insertion of a rewindable id failed?");
+                entity
+            });
+        }
+
+        for record in snapshot {
+            let entity = living[&record.id];
+
+            // Interpolate from where the entity is now, rather than from where
+            // it was before the recorded tick.
+            let mut locomotor = record.locomotor;
+            locomotor.old_pos = locomotors.get(entity)
+                .map_or(locomotor.pos, |current| current.pos);
+
+            let tether = record.tether.and_then(|[a, b]| {
+                match (living.get(&a), living.get(&b)) {
+                    (Some(&a), Some(&b)) => Some(Tether::between(a, b)),
+                    _ => None,
+                }
+            });
+
+            restore(&mut locomotors, entity, Some(locomotor));
+            restore(&mut transforms, entity, Some(record.transform));
+            restore(&mut lasers, entity, record.laser);
+            restore(&mut motion_models, entity, record.motion_model);
+            restore(&mut lifetimes, entity, record.lifetime);
+            restore(&mut tethers, entity, tether);
+            restore(&mut time_domains, entity, record.time_domain);
+            restore(&mut pickups, entity, record.pickup);
+            restore(&mut bounding_boxes, entity, record.bounding_box);
+            restore(&mut drags, entity, record.drag);
+            restore(&mut accumulators, entity, record.accumulator);
+            restore(&mut sprite_renderers, entity, record.sprite);
+            restore(&mut tints, entity, record.tint);
+            restore(&mut healths, entity, record.health);
+            restore(&mut shields, entity, record.shield);
+            restore(&mut dashes, entity, record.dash);
+            restore(&mut polarizeds, entity, record.polarized);
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//! This module keeps track of how long the current run has lasted. Other run
+//! statistics are counted by the systems responsible for the events in
+//! question.
+use amethyst::core::Time;
+use amethyst::ecs::{Read, System, Write};
+
+use crate::resources::{RunStats, TimeScale};
+
+pub struct RunStatsSystem;
+
+impl<'s> System<'s> for RunStatsSystem {
+    type SystemData = (
+        Write<'s, RunStats>,
+        Read<'s, TimeScale>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (mut stats, time_scale, time): Self::SystemData) {
+        stats.real_time += time.fixed_seconds();
+        stats.survived += time.fixed_seconds();
+        stats.scaled_time += time_scale.0 * time.fixed_seconds();
+    }
+}
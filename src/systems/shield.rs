@@ -0,0 +1,181 @@
+//! This module raises and lowers the players' shields in response to input,
+//! and deflects or destroys lasers that touch a raised shield. It must run
+//! before the `LaserCollisionSystem`, so that deflected lasers never get the
+//! chance to hit the player behind the shield.
+//!
+//! A shield faces the direction of its player's movement input, and keeps
+//! facing the same way when there is no input. Shields boosted by a pickup
+//! cover every direction. Raised shields are drawn by the `OverlaySystem`.
+//!
+//! Fences can not be deflected, since they are held in place by their anchors.
+use amethyst::core::{Time, Transform};
+use amethyst::ecs::{
+    Entities,
+    Join,
+    Read,
+    ReadStorage,
+    System,
+    WriteStorage,
+};
+
+use crate::collisions::arc_collision::*;
+use crate::components::{
+    Health,
+    Laser,
+    MotionModel,
+    Player,
+    RelativeLocomotor,
+    Shield,
+    ShieldMode,
+    Tether,
+};
+use crate::resources::TickInput;
+use crate::vector::prelude::*;
+
+pub struct ShieldSystem;
+
+impl<'s> System<'s> for ShieldSystem {
+    // TODO: is this idiomatic? Can I package these in some more convenient
+    // structure?
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, Health>,
+        ReadStorage<'s, Laser>,
+        ReadStorage<'s, Tether>,
+        WriteStorage<'s, Shield>,
+        WriteStorage<'s, RelativeLocomotor>,
+        WriteStorage<'s, MotionModel>,
+        WriteStorage<'s, Transform>,
+        Read<'s, TickInput>,
+        Read<'s, Time>,
+        Entities<'s>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            players,
+            healths,
+            lasers,
+            tethers,
+            mut shields,
+            mut locomotors,
+            mut motion_models,
+            mut transforms,
+            input,
+            time,
+            entities,
+        ): Self::SystemData
+    ) {
+        let delta = time.fixed_seconds();
+
+        // Update every player's shield, and gather the arcs of the raised ones.
+        let mut arcs = Vec::new();
+        let player_iter = (
+            &players,
+            &locomotors,
+            &mut shields,
+            (&healths).maybe(),
+        ).join();
+        for (player, locomotor, shield, health) in player_iter {
+            let alive = health.map_or(true, |health| !health.is_dead());
+
+            // Face the direction of the movement input, if there is any.
+            let x_in = input.axis_value(&player.binding("x_in")).unwrap_or(0.0);
+            let y_in = input.axis_value(&player.binding("y_in")).unwrap_or(0.0);
+            let analog_in = player.config.analog.process(Vec2::new(x_in, y_in));
+            if analog_in != Vec2::zeros() {
+                shield.facing = analog_in.normalize();
+            }
+
+            let held = input.action_is_down(&player.binding("shield"))
+                .unwrap_or(false);
+            let boosted = shield.is_boosted();
+            let half_angle = shield.half_angle();
+            shield.boosted_for = (shield.boosted_for - delta).max(0.0);
+            shield.raised = alive && (boosted || (held && shield.energy > 0.0));
+
+            let config = shield.config;
+            if shield.raised {
+                // A boosted shield is free.
+                if !boosted {
+                    shield.energy =
+                        (shield.energy - config.drain_rate * delta).max(0.0);
+                }
+                arcs.push((
+                    CircularArc {
+                        center: locomotor.pos,
+                        radius: config.radius,
+                        facing: shield.facing,
+                        half_angle,
+                    },
+                    config.mode,
+                ));
+            } else {
+                shield.energy = (shield.energy + config.regen_rate * delta)
+                    .min(config.max_energy);
+            }
+        }
+
+        if arcs.is_empty() {
+            return;
+        }
+
+        // Deflect or destroy the lasers touching a shield. Fences are excluded.
+        let laser_iter = (
+            &entities,
+            &lasers,
+            &mut locomotors,
+            &mut transforms,
+            !&tethers,
+        ).join();
+        for (entity, laser, locomotor, transform, _) in laser_iter {
+            if locomotor.velocity == Vec2::zeros() {
+                continue;
+            }
+
+            let laser_dir = locomotor.velocity.normalize();
+            let half_laser_vec = (laser.len * 0.5) * laser_dir;
+            let laser_top = locomotor.pos + half_laser_vec;
+            let laser_bot = locomotor.pos - half_laser_vec;
+
+            for (arc, mode) in arcs.iter() {
+                let contact = line_intersects_arc(laser_top, laser_bot, *arc);
+                let contact = match contact {
+                    Some(contact) => contact,
+                    None => continue,
+                };
+
+                // Only lasers heading into the shield are affected, so that a
+                // laser that was just deflected is not deflected back.
+                let normal = (contact - arc.center).normalize();
+                let incoming_speed = locomotor.velocity.dot(&normal);
+                if incoming_speed >= 0.0 {
+                    continue;
+                }
+
+                match mode {
+                    ShieldMode::Reflect => {
+                        // Mirror the velocity about the shield's surface. The
+                        // laser's motion model no longer applies once it has
+                        // been knocked off course.
+                        let velocity = locomotor.velocity
+                            - normal * (2.0 * incoming_speed);
+                        locomotor.velocity = velocity;
+                        motion_models.remove(entity);
+                        // Sprites are drawn along the y-axis.
+                        let theta = (-velocity[0]).atan2(velocity[1]);
+                        transform.set_rotation_2d(theta);
+                    },
+                    ShieldMode::Destroy => {
+                        // Panic in case of entity deletion failure.
+                        entities.delete(entity)
+                            .expect("The deletion of an entity failed?");
+                    },
+                }
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,24 @@
+//! This module counts down the slow time granted by pickups. It runs after
+//! pickups are collected, while the time scale itself is capped by the
+//! `TimeScalingSystem` at the start of the next tick.
+use amethyst::core::Time;
+use amethyst::ecs::{Read, System, Write};
+
+use crate::resources::SlowTime;
+
+pub struct SlowTimeSystem;
+
+impl<'s> System<'s> for SlowTimeSystem {
+    type SystemData = (
+        Write<'s, SlowTime>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (mut slow_time, time): Self::SystemData) {
+        // Slow time pickups last for a fixed amount of real time.
+        if slow_time.is_active() {
+            slow_time.remaining =
+                (slow_time.remaining - time.fixed_seconds()).max(0.0);
+        }
+    }
+}
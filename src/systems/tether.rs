@@ -0,0 +1,94 @@
+//! This module keeps tethered lasers stretched between their anchors. It must
+//! run after the anchors have moved, and before collisions are checked.
+//!
+//! Tethered lasers are scaled along the y-axis of their sprite to span the
+//! distance between their anchors.
+
+use amethyst::core::Transform;
+use amethyst::ecs::{Entities, Join, ReadStorage, System, WriteStorage};
+
+use crate::components::{Laser, RelativeLocomotor, Tether};
+use crate::resources::{LASER_SPRITE_LEN, LASER_SPRITE_WIDTH};
+use crate::vector::prelude::*;
+
+pub struct TetherSystem;
+
+impl<'s> System<'s> for TetherSystem {
+    // TODO: is this idiomatic? Can I package these in some more convenient
+    // structure?
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadStorage<'s, Tether>,
+        WriteStorage<'s, Laser>,
+        WriteStorage<'s, RelativeLocomotor>,
+        WriteStorage<'s, Transform>,
+        Entities<'s>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            tethers,
+            mut lasers,
+            mut locomotors,
+            mut transforms,
+            entities,
+        ): Self::SystemData
+    ) {
+        // Gather the anchor positions first, since the anchors and the lasers
+        // share the locomotor storage.
+        let spans: Vec<_> = (&entities, &tethers).join()
+            .map(|(entity, tether)| {
+                let [a, b] = tether.anchors;
+                let ends = locomotors.get(a)
+                    .and_then(|a| locomotors.get(b).map(|b| (a, b)))
+                    .map(|(a, b)| {
+                        (a.pos, b.pos, (a.velocity + b.velocity) * 0.5)
+                    });
+                (entity, ends)
+            })
+            .collect();
+
+        for (entity, ends) in spans {
+            let (a, b, velocity) = match ends {
+                Some(ends) => ends,
+                // A fence can not exist without both of its anchors.
+                None => {
+                    // Panic in case of entity deletion failure.
+                    entities.delete(entity)
+                        .expect("The deletion of an entity failed?");
+                    continue;
+                },
+            };
+
+            let span = b - a;
+            let len = span.norm();
+            let midpoint = a + span * 0.5;
+
+            if let Some(laser) = lasers.get_mut(entity) {
+                laser.len = len;
+            }
+
+            // The previous position was already recorded when the laser moved
+            // this tick, and is kept for interpolation.
+            if let Some(locomotor) = locomotors.get_mut(entity) {
+                locomotor.pos = midpoint;
+                locomotor.velocity = velocity;
+            }
+
+            if let Some(transform) = transforms.get_mut(entity) {
+                transform.set_translation(add_dim(midpoint.coords));
+                if len > 0.0 {
+                    // Sprites are drawn along the y-axis.
+                    transform.set_rotation_2d((-span[0]).atan2(span[1]));
+                }
+                transform.set_scale(Vec3::new(
+                    lasers.get(entity)
+                        .map_or(1.0, |laser| laser.width / LASER_SPRITE_WIDTH),
+                    len / LASER_SPRITE_LEN,
+                    1.0,
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! This module draws warning chevrons at the edge of the screen for lasers that
+//! are approaching a player from outside of the visible region. Each chevron
+//! points towards its laser, is drawn in the laser's color, and grows as the
+//! laser's estimated time to impact shrinks.
+//!
+//! The chevrons are drawn with the debug lines renderer, which is cleared every
+//! frame, so there is no indicator state to maintain.
+use std::path::Path;
+
+use amethyst::config::{Config, ConfigError};
+use amethyst::core::Transform;
+use amethyst::ecs::{Join, ReadStorage, System, Write};
+use amethyst::renderer::debug_drawing::DebugLines;
+use amethyst::renderer::palette::Srgba;
+use amethyst::utils::ortho_camera::CameraOrtho;
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::{Health, Laser, Player, RelativeLocomotor};
+use crate::util::prelude::*;
+use crate::vector::prelude::*;
+
+// TODO: hopefully remove the `Default` derivation pending this issue:
+// https://github.com/amethyst/amethyst/issues/1954
+#[derive(Default, Deserialize, Serialize)]
+pub struct ThreatIndicatorConfig {
+    /// The distance between the edge of the visible region and the tip of each
+    /// chevron, in world units.
+    edge_inset: StorageTy,
+    /// The size of a chevron for a laser that is far from impact.
+    min_size: StorageTy,
+    /// The size of a chevron for a laser that is about to hit.
+    max_size: StorageTy,
+    /// Lasers further than this many (scaled) seconds from impact are drawn at
+    /// the minimum size. Lasers that are not approaching are not drawn at all.
+    warning_time: StorageTy,
+}
+
+pub struct ThreatIndicatorSystem {
+    config: ThreatIndicatorConfig,
+}
+
+impl ThreatIndicatorSystem {
+    /// Builds a `ThreatIndicatorSystem` with the provided
+    /// `ThreatIndicatorConfig`.
+    pub fn from_config(config: impl Into<ThreatIndicatorConfig>) -> Self {
+        Self {
+            config: config.into(),
+        }
+    }
+
+    /// Builds a `ThreatIndicatorSystem` by reading the RON file at `path`.
+    pub fn from_config_path(
+        path: impl AsRef<Path>
+    ) -> Result<Self, ConfigError> {
+        // TODO: hopefully change this to just call load pending this issue:
+        // https://github.com/amethyst/amethyst/issues/1954
+        ThreatIndicatorConfig::load_no_fallback(path).map(Self::from_config)
+    }
+}
+
+impl<'s> System<'s> for ThreatIndicatorSystem {
+    // TODO: is this idiomatic? Can I package these in some more convenient
+    // structure?
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadStorage<'s, Laser>,
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, Health>,
+        ReadStorage<'s, RelativeLocomotor>,
+        ReadStorage<'s, CameraOrtho>,
+        ReadStorage<'s, Transform>,
+        Write<'s, DebugLines>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            lasers,
+            players,
+            healths,
+            locomotors,
+            cameras,
+            transforms,
+            mut debug_lines,
+        ): Self::SystemData
+    ) {
+        let config = &self.config;
+
+        // Find the center and half extents of the region guaranteed to be
+        // visible through the camera.
+        let camera = (&cameras, &transforms).join().next();
+        let (center, half_extents) = match camera {
+            Some((camera, transform)) => {
+                let translation = transform.translation();
+                let coords = &camera.world_coordinates;
+                (
+                    Point2::new(
+                        translation[0] + (coords.left + coords.right) * 0.5,
+                        translation[1] + (coords.bottom + coords.top) * 0.5,
+                    ),
+                    Vec2::new(
+                        (coords.right - coords.left) * 0.5,
+                        (coords.top - coords.bottom) * 0.5,
+                    ),
+                )
+            },
+            // Without a camera there is no screen edge to draw on.
+            None => return,
+        };
+
+        // Get the positions of the living players.
+        // TODO: is this idiomatic?
+        let player_iter = (&players, &locomotors, (&healths).maybe()).join();
+        let player_positions: Vec<_> = player_iter
+            .filter(|(_, _, health)| health.map_or(true, |h| !h.is_dead()))
+            .map(|(_, l, _)| l.pos)
+            .collect();
+
+        let inset_extents =
+            half_extents - Vec2::from_element(config.edge_inset);
+
+        for (laser, locomotor) in (&lasers, &locomotors).join() {
+            let offset = locomotor.pos - center;
+
+            // Lasers that are already on screen need no warning.
+            if offset[0].abs() <= half_extents[0]
+                && offset[1].abs() <= half_extents[1]
+            {
+                continue;
+            }
+
+            // Estimate the time to impact from the speed at which the laser is
+            // closing in on each player, and warn about the most urgent one.
+            let time_to_impact = player_positions.iter()
+                .filter_map(|&player_pos| {
+                    let to_player = player_pos - locomotor.pos;
+                    let dist = to_player.norm();
+                    if dist == 0.0 {
+                        return None;
+                    }
+                    let closing_speed =
+                        locomotor.velocity.dot(&to_player) / dist;
+                    if closing_speed <= 0.0 {
+                        None
+                    } else {
+                        Some(dist / closing_speed)
+                    }
+                })
+                .fold(None, |min: Option<StorageTy>, t| {
+                    Some(min.map_or(t, |min| min.min(t)))
+                });
+            let time_to_impact = match time_to_impact {
+                Some(time_to_impact) => time_to_impact,
+                // The laser is not approaching anybody.
+                None => continue,
+            };
+
+            // Urgency goes from 0.0 at the warning time to 1.0 on impact.
+            let urgency = 1.0 - clamp(
+                time_to_impact / config.warning_time,
+                0.0,
+                1.0,
+            );
+            let size = config.min_size
+                + (config.max_size - config.min_size) * urgency;
+
+            // Project the laser's position onto the inset edge of the visible
+            // region, along the ray from the center of the screen.
+            let dir = offset.normalize();
+            let t = (inset_extents[0] / dir[0].abs())
+                .min(inset_extents[1] / dir[1].abs());
+            let tip = center + dir * t;
+
+            // The two arms of the chevron trail behind its tip.
+            let normal = Vec2::new(-dir[1], dir[0]) * (size * 0.6);
+            let back = tip - dir * size;
+            let arm_1 = back + normal;
+            let arm_2 = back - normal;
+
+            let (r, g, b) = laser.color;
+            let color = Srgba::new(r, g, b, 1.0);
+            debug_lines.draw_line(to_point3(tip), to_point3(arm_1), color);
+            debug_lines.draw_line(to_point3(tip), to_point3(arm_2), color);
+        }
+    }
+}
+
+/// Lifts a point in the xy plane into 3D space for the debug lines renderer.
+fn to_point3(point: Point2) -> Point3 {
+    Point3::new(point[0], point[1], 0.0)
+}
@@ -1,19 +1,183 @@
-//! This module updates the current time scaling factor as a function of how
-//! long the player has been holding the input. As the player provides input,
-//! the time scaling factor is increased (meaning time speeds up). After input
-//! is no longer being provided, the time scaling factor is decreased. The value
-//! is clamped between a configurable maximum and minium time scaling value.
+//! This module updates the current time scaling factor as a function of what
+//! the players are doing. The configured `TimeScaleDriver` picks a target time
+//! scale between a configurable minimum and maximum, and the time scale eases
+//! towards that target with separate attack (speeding up) and release (slowing
+//! down) times.
+//!
+//! By default, time is driven by the players' input. Partial input moves time
+//! towards a proportionally partial time scale, so that half deflecting an
+//! analog stick lets time run at roughly half speed. Input is processed with
+//! the players' analog configuration, so that the deadzones agree with those
+//! used for movement. With several players, time follows whichever living
+//! player is providing the most input. Time may instead follow the players'
+//! speed, or slow down as lasers close in on them.
+//!
+//! While a slow time pickup is active, the time scale is additionally capped
+//! by the `SlowTime` resource, which the `SlowTimeSystem` counts down.
+//!
+//! Slow motion may be limited by the `Focus` meter, which drains while time is
+//! slowed below a threshold. An exhausted meter forces time back to full speed
+//! until it has recovered. Slow motion granted by pickups is free.
+//!
+//! Finally, every configured time channel is driven from the world's time
+//! scale, so that entities in other `TimeDomain`s can follow the world's time
+//! scaling more or less closely. This happens before anything moves, so that
+//! every system sees the same time scale within a tick.
+use std::collections::HashMap;
 use std::path::Path;
 
 use amethyst::config::{Config, ConfigError};
 use amethyst::core::Time;
-use amethyst::ecs::{Read, System, Write};
-use amethyst::input::{InputHandler, StringBindings};
+use amethyst::ecs::{Join, Read, ReadStorage, System, Write};
 
 use serde::{Deserialize, Serialize};
 
-use crate::resources::TimeScale;
+use crate::components::{
+    Health,
+    Laser,
+    MaxSpeed,
+    Player,
+    RelativeLocomotor,
+};
+use crate::resources::{
+    Focus,
+    SlowTime,
+    TickInput,
+    TimeChannels,
+    TimeScale,
+    WORLD_CHANNEL,
+};
 use crate::util::prelude::*;
+use crate::vector::prelude::*;
+
+/// How a time channel follows the world's time scale.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct ChannelConfig {
+    /// How much of the world's time scaling applies to the channel. At `1.0`
+    /// the channel matches the world, while at `0.0` it runs in real time.
+    influence: f32,
+    /// A constant factor applied on top. A factor of `0.0` freezes the
+    /// channel.
+    factor: f32,
+}
+
+impl ChannelConfig {
+    /// The channel's time scale when the world's time scale is `world`.
+    fn scale(self, world: f32) -> f32 {
+        self.factor * (1.0 + self.influence * (world - 1.0))
+    }
+}
+
+/// What the target time scale is a function of.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum TimeScaleDriver {
+    /// The magnitude of the most active living player's processed input.
+    Input,
+    /// The speed of the fastest living player, relative to their `MaxSpeed`.
+    Speed,
+    /// The distance between the nearest pair of living player and laser. Time
+    /// runs at the minimum time scale when a laser is within `near` of a
+    /// player, and at the maximum time scale when every laser is beyond `far`.
+    Proximity { near: StorageTy, far: StorageTy },
+}
+
+impl Default for TimeScaleDriver {
+    fn default() -> Self {
+        TimeScaleDriver::Input
+    }
+}
+
+/// How the time scale approaches its target.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum Easing {
+    /// Move at a constant rate, covering the full range between the minimum
+    /// and maximum time scales in the attack or release time.
+    Linear,
+    /// Close the remaining gap exponentially, with the attack or release time
+    /// as the time constant.
+    Exponential,
+    /// Like `Linear`, but easing in and out at the ends of the range.
+    Smoothstep,
+}
+
+impl Default for Easing {
+    fn default() -> Self {
+        Easing::Linear
+    }
+}
+
+impl Easing {
+    /// Moves `current` towards `target` over `delta` seconds, where `duration`
+    /// is the attack or release time and `min` and `max` bound the range of
+    /// time scales.
+    fn step(
+        self,
+        current: f32,
+        target: f32,
+        (min, max): (f32, f32),
+        duration: f32,
+        delta: f32,
+    ) -> f32 {
+        let range = max - min;
+        if duration <= 0.0 || range <= 0.0 {
+            return target;
+        }
+
+        match self {
+            Easing::Linear => {
+                approach(current, target, range * delta / duration)
+            },
+            Easing::Exponential => {
+                current + (target - current) * (1.0 - (-delta / duration).exp())
+            },
+            Easing::Smoothstep => {
+                // Move linearly through the smoothstep's input rather than
+                // through the time scale itself. This keeps no state beyond
+                // the current time scale, and still settles exactly on partial
+                // targets.
+                let normalize =
+                    |scale: f32| clamp((scale - min) / range, 0.0, 1.0);
+                let progress = approach(
+                    inverse_smoothstep(normalize(current)),
+                    inverse_smoothstep(normalize(target)),
+                    delta / duration,
+                );
+                min + range * smoothstep(progress)
+            },
+        }
+    }
+}
+
+/// Moves `current` towards `target` by at most `max_step`.
+fn approach(current: f32, target: f32, max_step: f32) -> f32 {
+    if current < target {
+        (current + max_step).min(target)
+    } else {
+        (current - max_step).max(target)
+    }
+}
+
+fn smoothstep(x: f32) -> f32 {
+    x * x * (3.0 - 2.0 * x)
+}
+
+/// The inverse of `smoothstep` over `[0, 1]`.
+fn inverse_smoothstep(y: f32) -> f32 {
+    0.5 - ((1.0 - 2.0 * y).asin() / 3.0).sin()
+}
+
+/// The rates of the `Focus` meter. Rates are fractions of the full meter per
+/// real second.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct FocusConfig {
+    /// The meter drains while the time scale is below this value.
+    threshold: f32,
+    drain_rate: f32,
+    /// The meter refills while time runs at the maximum time scale.
+    regen_rate: f32,
+    /// The fill at which an exhausted meter may be used again.
+    recovery_fill: f32,
+}
 
 // TODO: hopefully remove the `Default` derivation pending this issue:
 // https://github.com/amethyst/amethyst/issues/1954
@@ -23,10 +187,22 @@ pub struct TimeScalingConfig {
     minimum_time_scale: f32,
     /// The maximum allowed timescale.
     maximum_time_scale: f32,
-    /// The factor by which time scaling in the positive direction is scaled.
-    time_scale_positive_scaling_factor: f32,
-    /// The factor by which time scaling in the negative direction is scaled.
-    time_scale_negative_scaling_factor: f32,
+    /// What the target time scale follows.
+    #[serde(default)]
+    driver: TimeScaleDriver,
+    /// How the time scale approaches its target.
+    #[serde(default)]
+    easing: Easing,
+    /// The time it takes to speed up, in real seconds.
+    attack_time: f32,
+    /// The time it takes to slow down, in real seconds.
+    release_time: f32,
+    /// The named time channels besides the world's own.
+    #[serde(default)]
+    channels: HashMap<String, ChannelConfig>,
+    /// The focus meter. Without one, slow motion is unlimited.
+    #[serde(default)]
+    focus: Option<FocusConfig>,
 }
 
 pub struct TimeScalingSystem {
@@ -54,35 +230,210 @@ impl TimeScalingSystem {
 impl<'s> System<'s> for TimeScalingSystem {
     // TODO: is this idiomatic? Can I package these in some more convenient
     // structure?
+    #[allow(clippy::type_complexity)]
     type SystemData = (
-        Read<'s, InputHandler<StringBindings>>,
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, Health>,
+        ReadStorage<'s, RelativeLocomotor>,
+        ReadStorage<'s, MaxSpeed>,
+        ReadStorage<'s, Laser>,
+        Read<'s, TickInput>,
         Write<'s, TimeScale>,
+        Read<'s, SlowTime>,
+        Write<'s, Focus>,
+        Write<'s, TimeChannels>,
         Read<'s, Time>,
     );
 
     fn run(
         &mut self,
         (
+            players,
+            healths,
+            locomotors,
+            max_speeds,
+            lasers,
             input,
             mut time_scale,
+            slow_time,
+            mut focus,
+            mut channels,
             time
         ): Self::SystemData
     ) {
         let config = &self.config;
-        // Grab the raw input values.
-        let x_in = input.axis_value("x_in").unwrap_or(0.0);
-        let y_in = input.axis_value("y_in").unwrap_or(0.0);
+        let living_players = (
+            &players,
+            &locomotors,
+            (&max_speeds).maybe(),
+            (&healths).maybe(),
+        ).join()
+            .filter(|(_, _, _, health)| {
+                health.map_or(true, |health| !health.is_dead())
+            });
+
+        // How far towards full speed the driver pushes time, from `0.0` to
+        // `1.0`.
+        let activity = match config.driver {
+            // Time follows whichever living player is providing the most
+            // input. Input is processed the same way each player's movement
+            // does.
+            TimeScaleDriver::Input => living_players
+                .map(|(player, _, _, _)| {
+                    // Grab the raw input values.
+                    let x_in = input.axis_value(&player.binding("x_in"));
+                    let y_in = input.axis_value(&player.binding("y_in"));
+                    let raw = Vec2::new(
+                        x_in.unwrap_or(0.0),
+                        y_in.unwrap_or(0.0),
+                    );
+                    player.config.analog.process(raw).norm()
+                })
+                .fold(0.0, StorageTy::max),
+            // Time follows whichever living player is moving the fastest.
+            TimeScaleDriver::Speed => living_players
+                .map(|(player, locomotor, max_speed, _)| {
+                    let max_speed = max_speed.map_or(
+                        player.config.max_speed,
+                        |max_speed| max_speed.0,
+                    );
+                    if max_speed > 0.0 {
+                        (locomotor.velocity.norm() / max_speed).min(1.0)
+                    } else {
+                        1.0
+                    }
+                })
+                .fold(0.0, StorageTy::max),
+            // Time follows whichever living player is closest to a laser.
+            TimeScaleDriver::Proximity { near, far } => {
+                let player_positions: Vec<_> = living_players
+                    .map(|(_, locomotor, _, _)| locomotor.pos)
+                    .collect();
+                let nearest = (&lasers, &locomotors)
+                    .join()
+                    .flat_map(|(laser, laser_locomotor)| {
+                        player_positions.iter().map(move |pos| {
+                            // Approximate the laser by a circle around its
+                            // center.
+                            let dist = (*pos - laser_locomotor.pos).norm();
+                            (dist - laser.len * 0.5).max(0.0)
+                        })
+                    })
+                    .fold(std::f32::INFINITY, StorageTy::min);
+                if far > near {
+                    clamp((nearest - near) / (far - near), 0.0, 1.0)
+                } else if nearest > near {
+                    1.0
+                } else {
+                    0.0
+                }
+            },
+        };
+
+        // The time scale we are heading towards. An exhausted focus meter
+        // forces time back to full speed.
+        let target = if focus.exhausted {
+            config.maximum_time_scale
+        } else {
+            config.minimum_time_scale
+                + (config.maximum_time_scale - config.minimum_time_scale)
+                    * activity
+        };
+
+        // Ease towards the target without overshooting it, speeding up and
+        // slowing down at their own rates.
+        let duration = if time_scale.0 < target {
+            config.attack_time
+        } else {
+            config.release_time
+        };
+        let new_time_scale = config.easing.step(
+            time_scale.0,
+            target,
+            (config.minimum_time_scale, config.maximum_time_scale),
+            duration,
+            time.fixed_seconds(),
+        );
 
-        let time_scale_delta = if x_in == 0.0 && y_in == 0.0 {
-            config.time_scale_negative_scaling_factor * time.delta_seconds()
+        let slowed_by_pickup = slow_time.is_active();
+        let maximum_time_scale = if slowed_by_pickup {
+            config.maximum_time_scale.min(slow_time.max_time_scale)
         } else {
-            config.time_scale_positive_scaling_factor * time.delta_seconds()
+            config.maximum_time_scale
         };
 
         time_scale.0 = clamp(
-            time_scale.0 + time_scale_delta,
-            self.config.minimum_time_scale,
-            self.config.maximum_time_scale,
+            new_time_scale,
+            config.minimum_time_scale.min(maximum_time_scale),
+            maximum_time_scale,
         );
+
+        if let Some(focus_config) = config.focus {
+            let delta = time.fixed_seconds();
+            if slowed_by_pickup {
+                // Slow motion granted by a pickup is free.
+            } else if time_scale.0 < focus_config.threshold {
+                focus.fill =
+                    (focus.fill - focus_config.drain_rate * delta).max(0.0);
+                if focus.fill <= 0.0 {
+                    focus.exhausted = true;
+                }
+            } else if time_scale.0 >= config.maximum_time_scale {
+                focus.fill =
+                    (focus.fill + focus_config.regen_rate * delta).min(1.0);
+            }
+
+            if focus.exhausted && focus.fill >= focus_config.recovery_fill {
+                focus.exhausted = false;
+            }
+        }
+
+        channels.set(WORLD_CHANNEL, time_scale.0);
+        for (name, channel) in config.channels.iter() {
+            channels.set(name, channel.scale(time_scale.0));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inverse_smoothstep_round_trips() {
+        for i in 0..=100 {
+            let x = i as f32 / 100.0;
+            let round_trip = inverse_smoothstep(smoothstep(x));
+            assert!(
+                (round_trip - x).abs() < 1e-4,
+                "{} came back as {}",
+                x,
+                round_trip,
+            );
+        }
+    }
+
+    #[test]
+    fn easing_settles_on_target() {
+        let easings = [Easing::Linear, Easing::Exponential, Easing::Smoothstep];
+        // Partial targets are reached from either side.
+        let moves = [(1.0, 0.5), (0.2, 0.7), (0.3, 0.3)];
+        for &easing in easings.iter() {
+            for &(start, target) in moves.iter() {
+                // Ease for ten times the attack or release time.
+                let mut scale = start;
+                for _ in 0..100 {
+                    scale = easing.step(scale, target, (0.2, 1.0), 0.1, 0.01);
+                }
+                assert!(
+                    (scale - target).abs() < 1e-4,
+                    "{:?} went from {} to {} instead of {}",
+                    easing,
+                    start,
+                    scale,
+                    target,
+                );
+            }
+        }
     }
 }
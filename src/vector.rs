@@ -11,6 +11,9 @@ pub type StorageTy = f32;
 /// A 2D point in geometric space. The underlying `Vec2` may be accessed with
 /// the `coords` property.
 pub type Point2 = nalgebra::Point2<StorageTy>;
+/// A 3D point in geometric space. This is mostly needed when handing 2D points
+/// to the renderer.
+pub type Point3 = nalgebra::Point3<StorageTy>;
 /// A 2D vector, with all the benefits it brings.
 pub type Vec2 = nalgebra::Vector2<StorageTy>;
 /// A 3D vector, with all the benefits it brings.
@@ -30,6 +33,7 @@ pub mod prelude {
     pub use super::StorageTy;
 
     pub use super::Point2;
+    pub use super::Point3;
     pub use super::Vec2;
     pub use super::Vec3;
     pub use super::Rot2;